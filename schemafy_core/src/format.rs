@@ -0,0 +1,183 @@
+//! Strings of the `format`s which `string_formats` turns into newtypes that
+//! check the value when it is created or deserialized.
+
+use std::{fmt, ops::Deref, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use crate::validate::ValidationError;
+
+macro_rules! format_newtype {
+    ($(#[$doc:meta])* $name:ident, $format:literal, $is_valid:path) => {
+        $(#[$doc])*
+        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(value: String) -> Result<Self, ValidationError> {
+                if $is_valid(&value) {
+                    Ok($name(value))
+                } else {
+                    Err(ValidationError::new(format!(
+                        "`{}` is not a valid {}",
+                        value, $format
+                    )))
+                }
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ValidationError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $name::new(s.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                $name::new(String::deserialize(deserializer)?).map_err(de::Error::custom)
+            }
+        }
+    };
+}
+
+format_newtype!(
+    /// An email address (`format: email`), a local part and a hostname
+    /// separated by `@`.
+    Email,
+    "email address",
+    is_email
+);
+
+format_newtype!(
+    /// A hostname (`format: hostname`) as in RFC 1123.
+    Hostname,
+    "hostname",
+    is_hostname
+);
+
+format_newtype!(
+    /// An absolute URI (`format: uri`), which starts with a scheme.
+    Uri,
+    "URI",
+    is_uri
+);
+
+fn is_email(value: &str) -> bool {
+    match value.rsplit_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !local.contains(|c: char| c.is_whitespace() || c.is_control())
+                && is_hostname(domain)
+        }
+        None => false,
+    }
+}
+
+fn is_hostname(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 253
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+fn is_uri(value: &str) -> bool {
+    let scheme = match value.split_once(':') {
+        Some((scheme, _)) => scheme,
+        None => return false,
+    };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !value.contains(|c: char| c.is_whitespace() || c.is_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_values_round_trip() {
+        for json in &[
+            r#""user.name+tag@example.com""#,
+            r#""a@localhost""#,
+            r#""xn--bcher-kva.example""#,
+            r#""https://example.com/a?b#c""#,
+            r#""urn:isbn:0451450523""#,
+        ] {
+            let valid = if json.contains('@') {
+                serde_json::from_str::<Email>(json).map(|v| serde_json::to_string(&v))
+            } else if json.contains(':') {
+                serde_json::from_str::<Uri>(json).map(|v| serde_json::to_string(&v))
+            } else {
+                serde_json::from_str::<Hostname>(json).map(|v| serde_json::to_string(&v))
+            };
+            assert_eq!(valid.unwrap().unwrap(), *json);
+        }
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        for email in &[
+            "",
+            "@example.com",
+            "a@",
+            "a b@example.com",
+            "a@-example.com",
+        ] {
+            assert!(email.parse::<Email>().is_err(), "{}", email);
+        }
+        for hostname in &["", "a..b", "-a.com", "a_b.com", &"a".repeat(64)] {
+            assert!(hostname.parse::<Hostname>().is_err(), "{}", hostname);
+        }
+        for uri in &["", "example.com", "1http://a", "http://a b", ":a"] {
+            assert!(uri.parse::<Uri>().is_err(), "{}", uri);
+        }
+        let err = serde_json::from_str::<Email>(r#""nope""#).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`nope` is not a valid email address"),
+            "{}",
+            err
+        );
+    }
+}
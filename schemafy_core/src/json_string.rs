@@ -0,0 +1,81 @@
+//! Serializes a value as a string containing its JSON, used for strings
+//! with `contentMediaType: application/json` and a `contentSchema`.
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let json = serde_json::to_string(value).map_err(ser::Error::custom)?;
+    serializer.serialize_str(&json)
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: de::DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    let json = String::deserialize(deserializer)?;
+    serde_json::from_str(&json).map_err(de::Error::custom)
+}
+
+/// The same for an optional value, `None` is `null` instead of a string.
+pub mod option {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: de::DeserializeOwned,
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|json| serde_json::from_str(&json).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(PartialEq, Debug, Deserialize, Serialize)]
+    struct Envelope {
+        #[serde(with = "super")]
+        payload: Vec<i32>,
+        #[serde(default, with = "super::option")]
+        extra: Option<bool>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"payload":"[1,2]","extra":null}"#;
+        let envelope: Envelope = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            envelope,
+            Envelope {
+                payload: vec![1, 2],
+                extra: None,
+            }
+        );
+        assert_eq!(serde_json::to_string(&envelope).unwrap(), json);
+    }
+
+    #[test]
+    fn rejects_invalid_content() {
+        assert!(serde_json::from_str::<Envelope>(r#"{"payload":"[1,"}"#).is_err());
+        assert!(serde_json::from_str::<Envelope>(r#"{"payload":"[]","extra":"1"}"#).is_err());
+    }
+}
@@ -1 +1,27 @@
-pub mod one_or_many;
+mod format;
+pub mod json_string;
+pub mod merge_patch;
+pub mod one_or_many;
+mod secret;
+pub mod string_number;
+pub mod validate;
+
+/// The wrapper used for sensitive strings when `redact_secrets` is enabled.
+pub use secret::Secret;
+
+/// The strings used for the `email`, `hostname` and `uri` formats when
+/// `string_formats` is enabled.
+pub use format::{Email, Hostname, Uri};
+
+/// The map used for objects when `preserve_order` is enabled.
+#[cfg(feature = "indexmap")]
+pub use indexmap::IndexMap;
+
+/// The wrapper used for numbers when `ordered_floats` is enabled.
+#[cfg(feature = "ordered-float")]
+pub use ordered_float::OrderedFloat;
+
+/// The crate of the `DateTime`s used for integers with `format: unix-time`
+/// when `unix_timestamps` is enabled.
+#[cfg(feature = "chrono")]
+pub use chrono;
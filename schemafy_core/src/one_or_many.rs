@@ -1,5 +1,118 @@
 pub type OneOrMany<T> = Vec<T>;
 
+/// Wraps the deserializer of a single value so that it can be
+/// deserialized as `Some` when `T` is an `Option`, the primitive
+/// deserializers forward `deserialize_option` to `deserialize_any` which
+/// the visitor of `Option` rejects.
+struct One<D>(D);
+
+macro_rules! forward {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                self.0.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D> serde::Deserializer<'de> for One<D>
+where
+    D: serde::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_some(self.0)
+    }
+
+    forward! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_unit
+        deserialize_seq deserialize_map deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+}
+
 pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
 where
     T: serde::Deserialize<'de>,
@@ -22,39 +135,60 @@ where
             formatter.write_str("one or many")
         }
 
+        fn visit_unit<E>(self) -> Result<Vec<T>, E>
+        where
+            E: de::Error,
+        {
+            Deserialize::deserialize(().into_deserializer()).map(|v| vec![v])
+        }
+
+        fn visit_bool<E>(self, value: bool) -> Result<Vec<T>, E>
+        where
+            E: de::Error,
+        {
+            Deserialize::deserialize(One(value.into_deserializer())).map(|v| vec![v])
+        }
+
         fn visit_i64<E>(self, value: i64) -> Result<Vec<T>, E>
         where
             E: de::Error,
         {
-            Deserialize::deserialize(value.into_deserializer()).map(|v| vec![v])
+            Deserialize::deserialize(One(value.into_deserializer())).map(|v| vec![v])
         }
 
         fn visit_u64<E>(self, value: u64) -> Result<Vec<T>, E>
         where
             E: de::Error,
         {
-            Deserialize::deserialize(value.into_deserializer()).map(|v| vec![v])
+            Deserialize::deserialize(One(value.into_deserializer())).map(|v| vec![v])
+        }
+
+        fn visit_f64<E>(self, value: f64) -> Result<Vec<T>, E>
+        where
+            E: de::Error,
+        {
+            Deserialize::deserialize(One(value.into_deserializer())).map(|v| vec![v])
         }
 
         fn visit_str<E>(self, value: &str) -> Result<Vec<T>, E>
         where
             E: de::Error,
         {
-            Deserialize::deserialize(value.into_deserializer()).map(|v| vec![v])
+            Deserialize::deserialize(One(value.into_deserializer())).map(|v| vec![v])
         }
 
         fn visit_string<E>(self, value: String) -> Result<Vec<T>, E>
         where
             E: de::Error,
         {
-            Deserialize::deserialize(value.into_deserializer()).map(|v| vec![v])
+            Deserialize::deserialize(One(value.into_deserializer())).map(|v| vec![v])
         }
 
         fn visit_map<V>(self, visitor: V) -> Result<Self::Value, V::Error>
         where
             V: serde::de::MapAccess<'de2>,
         {
-            Deserialize::deserialize(MapAccessDeserializer::new(visitor)).map(|v| vec![v])
+            Deserialize::deserialize(One(MapAccessDeserializer::new(visitor))).map(|v| vec![v])
         }
 
         fn visit_seq<V>(self, visitor: V) -> Result<Self::Value, V::Error>
@@ -107,7 +241,7 @@ mod tests {
         );
     }
 
-    #[derive(Deserialize, Serialize, Debug, PartialEq)]
+    #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
     struct Test {
         x: i32,
         y: Option<String>,
@@ -145,4 +279,45 @@ mod tests {
             ])
         );
     }
+
+    proptest::proptest! {
+        #[test]
+        fn round_trip_int(value: Vec<i32>) {
+            let json = serde_json::to_string(&OneOrMany(value.clone())).unwrap();
+            proptest::prop_assert_eq!(from_str::<OneOrMany<i32>>(&json).unwrap(), OneOrMany(value));
+        }
+
+        #[test]
+        fn round_trip_bool(value: Vec<bool>) {
+            let json = serde_json::to_string(&OneOrMany(value.clone())).unwrap();
+            proptest::prop_assert_eq!(from_str::<OneOrMany<bool>>(&json).unwrap(), OneOrMany(value));
+        }
+
+        #[test]
+        fn round_trip_string(value: Vec<String>) {
+            let json = serde_json::to_string(&OneOrMany(value.clone())).unwrap();
+            proptest::prop_assert_eq!(from_str::<OneOrMany<String>>(&json).unwrap(), OneOrMany(value));
+        }
+
+        #[test]
+        fn round_trip_float(value: Vec<i16>) {
+            // Halves are represented exactly so the comparison is not affected by rounding
+            let value = value.into_iter().map(|v| f64::from(v) / 2.0).collect::<Vec<_>>();
+            let json = serde_json::to_string(&OneOrMany(value.clone())).unwrap();
+            proptest::prop_assert_eq!(from_str::<OneOrMany<f64>>(&json).unwrap(), OneOrMany(value));
+        }
+
+        #[test]
+        fn round_trip_option(value: Vec<Option<i32>>) {
+            let json = serde_json::to_string(&OneOrMany(value.clone())).unwrap();
+            proptest::prop_assert_eq!(from_str::<OneOrMany<Option<i32>>>(&json).unwrap(), OneOrMany(value));
+        }
+
+        #[test]
+        fn round_trip_struct(value: Vec<(i32, Option<String>)>) {
+            let value = value.into_iter().map(|(x, y)| Test { x, y }).collect::<Vec<_>>();
+            let json = serde_json::to_string(&OneOrMany(value.clone())).unwrap();
+            proptest::prop_assert_eq!(from_str::<OneOrMany<Test>>(&json).unwrap(), OneOrMany(value));
+        }
+    }
 }
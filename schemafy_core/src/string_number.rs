@@ -0,0 +1,93 @@
+//! Serializes a number as a string, used for the integers which APIs encode as
+//! strings, such as strings with the `int64` format, when `string_integers` is
+//! enabled.
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
+
+/// The same for an optional value, `None` is `null` instead of a string.
+pub mod option {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        match value {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        Option::<std::borrow::Cow<'de, str>>::deserialize(deserializer)?
+            .map(|s| s.parse().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(PartialEq, Debug, Deserialize, Serialize)]
+    struct Account {
+        #[serde(with = "super")]
+        id: u64,
+        #[serde(default, with = "super::option")]
+        balance: Option<i64>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let json = r#"{"id":"12345678901234567","balance":"-42"}"#;
+        let account: Account = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            account,
+            Account {
+                id: 12345678901234567,
+                balance: Some(-42),
+            }
+        );
+        assert_eq!(serde_json::to_string(&account).unwrap(), json);
+    }
+
+    #[test]
+    fn missing_and_null() {
+        let account: Account = serde_json::from_str(r#"{"id":"1"}"#).unwrap();
+        assert_eq!(account.balance, None);
+        let account: Account = serde_json::from_str(r#"{"id":"1","balance":null}"#).unwrap();
+        assert_eq!(account.balance, None);
+    }
+
+    #[test]
+    fn rejects_numbers_and_garbage() {
+        assert!(serde_json::from_str::<Account>(r#"{"id":1}"#).is_err());
+        assert!(serde_json::from_str::<Account>(r#"{"id":"one"}"#).is_err());
+    }
+}
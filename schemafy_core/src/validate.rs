@@ -0,0 +1,423 @@
+//! Runtime checks used by the validated types schemafy generates for
+//! constrained schemas.
+
+use std::fmt;
+
+use serde_json::Value;
+
+/// A value that does not satisfy a constraint of its schema.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ValidationError {
+    path: String,
+    message: String,
+}
+
+impl ValidationError {
+    pub fn new<S: Into<String>>(message: S) -> ValidationError {
+        ValidationError {
+            path: String::new(),
+            message: message.into(),
+        }
+    }
+
+    /// Marks the error as coming from the property or item `segment` of
+    /// the value, building a JSON pointer to the invalid value.
+    pub fn at(mut self, segment: &str) -> ValidationError {
+        self.path = format!("/{}{}", segment, self.path);
+        self
+    }
+
+    /// The JSON pointer to the invalid value, empty for the value itself.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "`{}`: ", self.path)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks `minLength`/`maxLength`.
+///
+/// As in JSON Schema the length is the number of Unicode scalar values
+/// (`char`s) and not the number of bytes.
+pub fn string_length(
+    value: &str,
+    min: Option<u64>,
+    max: Option<u64>,
+) -> Result<(), ValidationError> {
+    let length = value.chars().count() as u64;
+    if let Some(min) = min {
+        if length < min {
+            return Err(ValidationError::new(format!(
+                "string of length {} is shorter than the minimum length {}",
+                length, min
+            )));
+        }
+    }
+    if let Some(max) = max {
+        if length > max {
+            return Err(ValidationError::new(format!(
+                "string of length {} is longer than the maximum length {}",
+                length, max
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `minimum`/`maximum`, the bounds are exclusive if the
+/// corresponding `exclusiveMinimum`/`exclusiveMaximum` is set.
+pub fn range(
+    value: f64,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: bool,
+    exclusive_maximum: bool,
+) -> Result<(), ValidationError> {
+    if let Some(minimum) = minimum {
+        if value < minimum || (exclusive_minimum && value == minimum) {
+            return Err(ValidationError::new(format!(
+                "{} is less than the minimum {}",
+                value, minimum
+            )));
+        }
+    }
+    if let Some(maximum) = maximum {
+        if value > maximum || (exclusive_maximum && value == maximum) {
+            return Err(ValidationError::new(format!(
+                "{} is greater than the maximum {}",
+                value, maximum
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `multipleOf`.
+pub fn multiple_of(value: f64, multiple: f64) -> Result<(), ValidationError> {
+    let quotient = value / multiple;
+    if quotient.is_finite() && quotient != quotient.round() {
+        return Err(ValidationError::new(format!(
+            "{} is not a multiple of {}",
+            value, multiple
+        )));
+    }
+    Ok(())
+}
+
+/// Checks `minItems`/`maxItems`.
+pub fn items_length(
+    length: usize,
+    min: Option<u64>,
+    max: Option<u64>,
+) -> Result<(), ValidationError> {
+    let length = length as u64;
+    if let Some(min) = min {
+        if length < min {
+            return Err(ValidationError::new(format!(
+                "array of length {} has fewer than {} items",
+                length, min
+            )));
+        }
+    }
+    if let Some(max) = max {
+        if length > max {
+            return Err(ValidationError::new(format!(
+                "array of length {} has more than {} items",
+                length, max
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `minProperties`/`maxProperties`.
+pub fn properties_count(
+    count: usize,
+    min: Option<u64>,
+    max: Option<u64>,
+) -> Result<(), ValidationError> {
+    let count = count as u64;
+    if let Some(min) = min {
+        if count < min {
+            return Err(ValidationError::new(format!(
+                "object with {} properties has fewer than {} properties",
+                count, min
+            )));
+        }
+    }
+    if let Some(max) = max {
+        if count > max {
+            return Err(ValidationError::new(format!(
+                "object with {} properties has more than {} properties",
+                count, max
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `pattern`, which like in JSON Schema matches anywhere in the
+/// string unless it is anchored.
+#[cfg(feature = "regex")]
+pub fn pattern(value: &str, pattern: &str) -> Result<(), ValidationError> {
+    let regex = regex::Regex::new(pattern)
+        .map_err(|err| ValidationError::new(format!("invalid pattern `{}`: {}", pattern, err)))?;
+    if regex.is_match(value) {
+        Ok(())
+    } else {
+        Err(ValidationError::new(format!(
+            "`{}` does not match the pattern `{}`",
+            value, pattern
+        )))
+    }
+}
+
+/// Checks `not`, the value must not match the JSON of the schema `not`.
+pub fn not<T: serde::Serialize + ?Sized>(value: &T, not: &str) -> Result<(), ValidationError> {
+    let value = serde_json::to_value(value).map_err(|err| ValidationError::new(err.to_string()))?;
+    let schema = serde_json::from_str(not)
+        .map_err(|err| ValidationError::new(format!("invalid schema `{}`: {}", not, err)))?;
+    if matches(&value, &schema) {
+        Err(ValidationError::new(format!(
+            "`{}` matches the schema `{}` which it must not",
+            value, not
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether `value` matches `schema`, keywords which are not known (such as
+/// an unresolved `$ref`) match any value.
+fn matches(value: &Value, schema: &Value) -> bool {
+    let schema = match schema {
+        Value::Bool(b) => return *b,
+        Value::Object(schema) => schema,
+        _ => return true,
+    };
+    let number = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    let count = |keyword: &str| schema.get(keyword).and_then(Value::as_u64);
+    let schemas = |keyword: &str| {
+        schema
+            .get(keyword)
+            .and_then(Value::as_array)
+            .map_or(&[][..], |schemas| schemas)
+    };
+
+    if let Some(types) = schema.get("type") {
+        let types = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            _ => types.as_str().into_iter().collect::<Vec<_>>(),
+        };
+        if !types.is_empty() && !types.iter().any(|type_| has_type(value, type_)) {
+            return false;
+        }
+    }
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        if !values.contains(value) {
+            return false;
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            return false;
+        }
+    }
+    if !schemas("allOf").iter().all(|s| matches(value, s))
+        || (schema.contains_key("anyOf") && !schemas("anyOf").iter().any(|s| matches(value, s)))
+        || (schema.contains_key("oneOf")
+            && schemas("oneOf")
+                .iter()
+                .filter(|s| matches(value, s))
+                .count()
+                != 1)
+        || schema.get("not").is_some_and(|not| matches(value, not))
+    {
+        return false;
+    }
+
+    match value {
+        Value::String(s) => {
+            let length = s.chars().count() as u64;
+            if count("minLength").is_some_and(|min| length < min)
+                || count("maxLength").is_some_and(|max| length > max)
+            {
+                return false;
+            }
+            #[cfg(feature = "regex")]
+            {
+                if let Some(regex) = schema
+                    .get("pattern")
+                    .and_then(Value::as_str)
+                    .and_then(|pattern| regex::Regex::new(pattern).ok())
+                {
+                    if !regex.is_match(s) {
+                        return false;
+                    }
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or(f64::NAN);
+            // Draft 4 modifies `minimum` with a boolean, later drafts use a number
+            let exclusive = |keyword: &str, bound: Option<f64>| match schema.get(keyword) {
+                Some(Value::Bool(true)) => bound,
+                Some(exclusive) => exclusive.as_f64(),
+                None => None,
+            };
+            if number("minimum").is_some_and(|min| n < min)
+                || number("maximum").is_some_and(|max| n > max)
+                || exclusive("exclusiveMinimum", number("minimum")).is_some_and(|min| n <= min)
+                || exclusive("exclusiveMaximum", number("maximum")).is_some_and(|max| n >= max)
+                || number("multipleOf").is_some_and(|multiple| multiple_of(n, multiple).is_err())
+            {
+                return false;
+            }
+        }
+        Value::Array(items) => {
+            let length = items.len() as u64;
+            if count("minItems").is_some_and(|min| length < min)
+                || count("maxItems").is_some_and(|max| length > max)
+            {
+                return false;
+            }
+            if schema.get("uniqueItems") == Some(&Value::Bool(true))
+                && items
+                    .iter()
+                    .enumerate()
+                    .any(|(i, item)| items[..i].contains(item))
+            {
+                return false;
+            }
+            let all_match = match schema.get("items") {
+                Some(Value::Array(tuple)) => items.iter().zip(tuple).all(|(v, s)| matches(v, s)),
+                Some(item) => items.iter().all(|v| matches(v, item)),
+                None => true,
+            };
+            if !all_match {
+                return false;
+            }
+        }
+        Value::Object(object) => {
+            let length = object.len() as u64;
+            if count("minProperties").is_some_and(|min| length < min)
+                || count("maxProperties").is_some_and(|max| length > max)
+            {
+                return false;
+            }
+            if !schemas("required")
+                .iter()
+                .filter_map(Value::as_str)
+                .all(|key| object.contains_key(key))
+            {
+                return false;
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (key, property) in object {
+                let property_schema = match properties.and_then(|p| p.get(key)) {
+                    Some(property_schema) => Some(property_schema),
+                    // Which properties `patternProperties` covers is not known so
+                    // `additionalProperties` only applies without it
+                    None if schema
+                        .get("patternProperties")
+                        .and_then(Value::as_object)
+                        .is_none_or(|patterns| patterns.is_empty()) =>
+                    {
+                        schema.get("additionalProperties")
+                    }
+                    None => None,
+                };
+                if property_schema.is_some_and(|s| !matches(property, s)) {
+                    return false;
+                }
+            }
+        }
+        _ => (),
+    }
+    true
+}
+
+fn has_type(value: &Value, type_: &str) -> bool {
+    match (type_, value) {
+        ("null", Value::Null)
+        | ("boolean", Value::Bool(_))
+        | ("number", Value::Number(_))
+        | ("string", Value::String(_))
+        | ("array", Value::Array(_))
+        | ("object", Value::Object(_)) => true,
+        ("integer", Value::Number(n)) => {
+            n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_length_counts_chars() {
+        assert!(string_length("åäö", Some(3), Some(3)).is_ok());
+        assert!(string_length("ab", Some(3), None).is_err());
+        assert!(string_length("abcd", None, Some(3)).is_err());
+        assert!(string_length("", None, None).is_ok());
+    }
+
+    #[test]
+    fn range_respects_exclusive_bounds() {
+        assert!(range(1.0, Some(1.0), Some(2.0), false, false).is_ok());
+        assert!(range(1.0, Some(1.0), None, true, false).is_err());
+        assert!(range(2.0, None, Some(2.0), false, true).is_err());
+        assert!(range(3.0, None, Some(2.0), false, false).is_err());
+    }
+
+    #[test]
+    fn multiple_of_allows_fractions() {
+        assert!(multiple_of(1.5, 0.5).is_ok());
+        assert!(multiple_of(10.0, 3.0).is_err());
+    }
+
+    #[test]
+    fn not_rejects_matching_values() {
+        assert!(not("", r#"{"maxLength": 0}"#).is_err());
+        assert!(not("a", r#"{"maxLength": 0}"#).is_ok());
+        assert!(not(&2, r#"{"type": "integer", "enum": [1, 2]}"#).is_err());
+        assert!(not(&2.5, r#"{"type": "integer"}"#).is_ok());
+        assert!(not(&[1, 1], r#"{"uniqueItems": true}"#).is_ok());
+        assert!(not(&[1, 2], r#"{"items": {"minimum": 1}}"#).is_err());
+        let object = serde_json::json!({ "a": 1 });
+        assert!(not(&object, r#"{"required": ["a"]}"#).is_err());
+        assert!(not(&object, r#"{"additionalProperties": false}"#).is_ok());
+        assert!(not(
+            &object,
+            r#"{"anyOf": [{"required": ["b"]}, {"minProperties": 1}]}"#
+        )
+        .is_err());
+        assert!(not(&object, r#"{"not": {"type": "object"}}"#).is_ok());
+        assert!(not(&3, r#"{"minimum": 3, "exclusiveMinimum": true}"#).is_ok());
+        assert!(not(&3, r#"{"exclusiveMaximum": 4}"#).is_err());
+        // The empty defaults of the `Schema` of schemafy_lib do not restrict the value
+        assert!(not(
+            &object,
+            r#"{"type": [], "patternProperties": {}, "additionalProperties": false}"#
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn errors_display_their_path() {
+        let err = ValidationError::new("too long").at("b").at("a");
+        assert_eq!(err.path(), "/a/b");
+        assert_eq!(err.to_string(), "`/a/b`: too long");
+    }
+}
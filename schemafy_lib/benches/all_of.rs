@@ -0,0 +1,80 @@
+//! Times the generation of schemas which lean on `allOf`, run with
+//! `cargo bench -p schemafy_lib`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_json::{json, Map, Value};
+
+/// A chain of definitions where each one extends the previous one.
+fn chain(depth: usize) -> Value {
+    let mut definitions = Map::new();
+    definitions.insert(
+        "level0".into(),
+        json!({ "type": "object", "properties": { "base": { "type": "string" } } }),
+    );
+    let mut properties = Map::new();
+    for i in 1..depth {
+        definitions.insert(
+            format!("level{}", i),
+            json!({ "allOf": [
+                { "$ref": format!("#/definitions/level{}", i - 1) },
+                { "type": "object", "properties": { format!("field{}", i): { "type": "integer" } } }
+            ] }),
+        );
+        properties.insert(
+            format!("level{}", i),
+            json!({ "$ref": format!("#/definitions/level{}", i) }),
+        );
+    }
+    json!({ "type": "object", "properties": properties, "definitions": definitions })
+}
+
+/// Many definitions extending one large base, each also extended inline.
+fn wide(width: usize) -> Value {
+    let base: Map<_, _> = (0..width)
+        .map(|i| (format!("base{}", i), json!({ "type": "string" })))
+        .collect();
+    let mut definitions = Map::new();
+    definitions.insert(
+        "base".into(),
+        json!({ "type": "object", "properties": base }),
+    );
+    let mut properties = Map::new();
+    for i in 0..width {
+        definitions.insert(
+            format!("derived{}", i),
+            json!({ "allOf": [
+                { "$ref": "#/definitions/base" },
+                { "type": "object", "properties": { "own": { "type": "integer" } }, "required": ["own"] }
+            ] }),
+        );
+        properties.insert(
+            format!("inline{}", i),
+            json!({ "allOf": [
+                { "$ref": format!("#/definitions/derived{}", i) },
+                { "required": ["base0"] }
+            ] }),
+        );
+    }
+    json!({ "type": "object", "properties": properties, "definitions": definitions })
+}
+
+fn bench(c: &mut Criterion, name: &str, schema: Value) {
+    let schema = schemafy_lib::parse_value(&schema).unwrap();
+    let generator = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .build();
+    c.bench_function(name, |b| b.iter(|| generator.generate_schema(&schema)));
+}
+
+fn all_of(c: &mut Criterion) {
+    bench(c, "chain", chain(60));
+    bench(c, "wide", wide(100));
+}
+
+// A run of `wide` takes a few hundred milliseconds, fewer samples keep the runs short
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = all_of
+}
+criterion_main!(benches);
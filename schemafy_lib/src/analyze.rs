@@ -0,0 +1,98 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{const_enum, parse_value, walk_schema, Schema, SchemafyError, SimpleTypes};
+
+/// Keywords which only annotate a schema and so are not missed when ignored.
+const ANNOTATIONS: &[&str] = &["$id", "$anchor", "deprecated", "contentEncoding"];
+
+/// A construct of a schema which the generated types do not capture.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Unsupported {
+    /// The JSON pointer of the subschema containing the construct.
+    pub path: String,
+    /// The keyword of the construct.
+    pub keyword: String,
+    /// What happens to the construct.
+    pub message: String,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: `{}` {}", self.path, self.keyword, self.message)
+    }
+}
+
+/// Lists the parts of the schema `s` which would be ignored or would
+/// become `serde_json::Value` when generating types with the default
+/// options, without generating anything.
+pub fn analyze(s: &str) -> Result<Vec<Unsupported>, SchemafyError> {
+    let value: Value = serde_json::from_str(s)?;
+    let schema = parse_value(&value)?;
+    let mut unsupported = Vec::new();
+    walk_schema("#".to_string(), &schema, &mut |path, schema| {
+        let mut report = |keyword: &str, message: &str| {
+            unsupported.push(Unsupported {
+                path: path.to_string(),
+                keyword: keyword.to_string(),
+                message: message.to_string(),
+            })
+        };
+
+        // Keywords which are not part of `Schema` are dropped by the parser
+        if let (Some(Value::Object(raw)), Ok(Value::Object(parsed))) =
+            (value.pointer(&path[1..]), serde_json::to_value(schema))
+        {
+            for keyword in raw.keys() {
+                if !parsed.contains_key(keyword)
+                    && !keyword.starts_with("x-")
+                    && !ANNOTATIONS.contains(&keyword.as_str())
+                {
+                    report(keyword, "is not supported and is ignored");
+                }
+            }
+        }
+
+        if schema.not.is_some() {
+            report("not", "is ignored unless `validation` is set");
+        }
+        if !schema.pattern_properties.is_empty() {
+            report("patternProperties", "is ignored");
+        }
+        if schema.additional_items.is_some() {
+            report("additionalItems", "is ignored");
+        }
+        if schema.items.len() > 1 {
+            report("items", "is a tuple of which only the first schema is used");
+        }
+        if schema
+            .any_of
+            .as_ref()
+            .is_some_and(|any_of| any_of.len() >= 2)
+            && const_enum(schema).is_none()
+            && !is_one_or_many(schema.any_of.as_ref().unwrap())
+        {
+            report("anyOf", "becomes `serde_json::Value`");
+        }
+        let types = schema
+            .type_
+            .iter()
+            .filter(|type_| **type_ != SimpleTypes::Null)
+            .count();
+        if types >= 2 {
+            report(
+                "type",
+                "becomes `serde_json::Value` unless `untagged_type_unions` is set",
+            );
+        }
+    });
+    Ok(unsupported)
+}
+
+/// Whether the `anyOf` is a value or an array of such values, ignoring that
+/// the generator also resolves `$ref`s to compare them.
+fn is_one_or_many(any_of: &[Schema]) -> bool {
+    any_of[1].type_.first() == Some(&SimpleTypes::Array)
+        && any_of[1].items.first() == Some(&any_of[0])
+}
@@ -0,0 +1,128 @@
+use serde_json::Value;
+
+use crate::{Schema, SchemafyError};
+
+/// A schema document which is bundled with others, along with the name of its
+/// file and its `$id`.
+pub struct Document {
+    pub file_name: String,
+    pub id: Option<String>,
+    pub schema: Schema,
+}
+
+impl Document {
+    /// The name of the type of the root, which is the name of the file up to
+    /// its extension as for any `$ref` to the root of another document.
+    fn root_name(&self) -> &str {
+        self.file_name.split('.').next().unwrap_or_default()
+    }
+
+    /// Whether the `$ref` `s` points into this document, by its file name or
+    /// its `$id`.
+    fn is_referred_by(&self, s: &str) -> bool {
+        let document = s.split('#').next().unwrap_or_default();
+        self.id
+            .as_deref()
+            .is_some_and(|id| id.trim_end_matches('#') == document)
+            || document.rsplit('/').next() == Some(self.file_name.as_str())
+    }
+}
+
+/// Bundles `documents` which refer to each other into a single schema whose
+/// definitions are the definitions of all the documents, along with the root
+/// of each document which is more than a collection of definitions.
+///
+/// The `$ref`s between the documents are rewritten to point into the bundle,
+/// those into other documents are left as they are. A definition which is in
+/// several documents is only kept once, unless the documents define it
+/// differently, which is a `SchemafyError::IdentifierCollision`.
+pub fn bundle(documents: &[Document]) -> Result<Schema, SchemafyError> {
+    let mut bundle: Schema = serde_json::from_value(serde_json::json!({}))?;
+    let empty = bundle.clone();
+    bundle.schema = documents.iter().find_map(|doc| doc.schema.schema.clone());
+    for (index, document) in documents.iter().enumerate() {
+        let mut value = serde_json::to_value(&document.schema)?;
+        rewrite_refs(&mut value, &|s| rewrite_ref(documents, index, s));
+        let mut root: Schema = serde_json::from_value(value)?;
+
+        let mut definitions = std::mem::take(&mut root.definitions);
+        if let Some(components) = root.components.take() {
+            definitions.extend(components.schemas);
+        }
+        root.id = None;
+        root.schema = None;
+        if root != empty {
+            definitions.insert(document.root_name().to_string(), root);
+        }
+        for (name, definition) in definitions {
+            match bundle.definitions.get(&name) {
+                Some(existing) if *existing != definition => {
+                    return Err(SchemafyError::IdentifierCollision(name))
+                }
+                Some(_) => (),
+                None => {
+                    bundle.definitions.insert(name, definition);
+                }
+            }
+        }
+    }
+    Ok(bundle)
+}
+
+/// The `$ref` `s` of the document at `index` as it points into the bundle,
+/// `None` if it points into a document which is not bundled.
+fn rewrite_ref(documents: &[Document], index: usize, s: &str) -> Option<String> {
+    let (document, pointer) = s.split_once('#').unwrap_or((s, ""));
+    let target = if document.is_empty() {
+        &documents[index]
+    } else {
+        documents.iter().find(|doc| doc.is_referred_by(s))?
+    };
+    // The OpenAPI components are bundled as definitions
+    let pointer = match pointer.strip_prefix("/components/schemas/") {
+        Some(name) => format!("/definitions/{}", name),
+        None => pointer.to_string(),
+    };
+    Some(if pointer.starts_with("/definitions/") {
+        format!("#{}", pointer)
+    } else {
+        format!("#/definitions/{}{}", target.root_name(), pointer)
+    })
+}
+
+/// Calls `f` on the `$ref`s of a schema and its subschemas, replacing them by
+/// what it returns. The values of a schema, such as its `enum`, are not
+/// schemas so they are left alone.
+fn rewrite_refs(value: &mut Value, f: &dyn Fn(&str) -> Option<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match (key.as_str(), value) {
+                    ("$ref", Value::String(s)) => {
+                        if let Some(rewritten) = f(s) {
+                            *s = rewritten;
+                        }
+                    }
+                    ("enum" | "const" | "default" | "examples", _) => (),
+                    // The keys of these are names, even `$ref` or `enum`
+                    (
+                        "definitions" | "properties" | "patternProperties" | "dependencies"
+                        | "schemas",
+                        Value::Object(schemas),
+                    ) => {
+                        for schema in schemas.values_mut() {
+                            rewrite_refs(schema, f);
+                        }
+                    }
+                    (_, value) => rewrite_refs(value, f),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                rewrite_refs(value, f);
+            }
+        }
+        _ => (),
+    }
+}
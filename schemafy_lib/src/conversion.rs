@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use proc_macro2::TokenStream;
+use serde_json::Value;
+
+use crate::{
+    const_enum, definitions, enum_variant_names, field_ident, is_ref_alias, is_required, reference,
+    variant_ident, without_lifetime, Expander, FieldType, Schema,
+};
+
+/// How the fields of a type are matched with the fields of the other
+/// version of the type in [`expand_conversions`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldMatching {
+    /// Fields match if their properties have the same name in the schemas.
+    PropertyName,
+    /// Fields match if they have the same name in Rust, so that for example
+    /// `userName` and `user_name` match.
+    FieldName,
+}
+
+struct Field {
+    property: String,
+    ident: syn::Ident,
+    typ: String,
+    default: bool,
+}
+
+enum Kind {
+    Struct(Vec<Field>),
+    /// The variant of each value of a unit enum.
+    Enum(Vec<(Value, TokenStream)>),
+    Alias(String),
+    /// A type which no conversion is generated for, such as an untagged enum.
+    Opaque,
+}
+
+struct Version {
+    path: String,
+    kinds: BTreeMap<String, Kind>,
+}
+
+impl Version {
+    fn new(root_name: Option<&str>, path: &str, schema: &Schema) -> Version {
+        let mut expander = Expander::new(root_name, "::schemafy_core::", schema);
+        let types = expander.expand_types(schema);
+        let mut kinds = BTreeMap::new();
+        for name in types.keys() {
+            kinds.insert(name.clone(), Kind::Opaque);
+        }
+        let definitions = root_name
+            .map(|name| (name, schema))
+            .into_iter()
+            .chain(definitions(schema).map(|(k, v)| (k.as_str(), v)))
+            .filter(|(_, def)| def.x_rust_skip != Some(true));
+        for (original_name, def) in definitions {
+            let pascal_case_name = expander.options.identifier_case.type_name(original_name);
+            let name = expander.type_name(&pascal_case_name);
+            if expander.borrowed.contains(&name) {
+                continue;
+            }
+            expander.current_type = pascal_case_name;
+            let kind = kind(&mut expander, original_name, def);
+            kinds.insert(name, kind);
+        }
+        Version {
+            path: path.to_string(),
+            kinds,
+        }
+    }
+
+    fn path(&self, name: &str) -> TokenStream {
+        format!("{}{}", self.path, name).parse().unwrap()
+    }
+
+    /// Resolves the type aliases of the type `typ`.
+    fn resolve<'a>(&'a self, mut typ: &'a str) -> &'a str {
+        // Aliases can't be cyclic but this guards against infinite loops anyway
+        for _ in 0..16 {
+            match self.kinds.get(typ) {
+                Some(Kind::Alias(alias)) => typ = alias,
+                _ => break,
+            }
+        }
+        typ
+    }
+}
+
+/// The kind of the type generated for the definition `def`, as decided by
+/// `Expander::expand_schema`.
+fn kind<'r>(expander: &mut Expander<'r>, original_name: &str, def: &'r Schema) -> Kind {
+    let current_type = expander.current_type.clone();
+    let schema = expander.schema(def);
+    let const_enum = const_enum(def);
+    let def = const_enum.as_ref().unwrap_or(def);
+    if is_ref_alias(def) {
+        let typ = expander.type_use(expander.type_ref(reference(def).unwrap()));
+        Kind::Alias(typ.split_whitespace().collect())
+    } else if !schema.properties.is_empty()
+        || schema.additional_properties == Some(Value::Bool(false))
+    {
+        let mut fields = Vec::new();
+        for (property, value) in expander.ordered(&schema.properties) {
+            expander.current_type.clone_from(&current_type);
+            expander.current_field.clone_from(property);
+            let required = is_required(&schema, property, value);
+            let field_type = match reference(value).filter(|_| value.x_extends == Some(true)) {
+                Some(ref_) => FieldType {
+                    typ: expander.type_use(expander.type_ref(ref_)),
+                    attributes: vec![],
+                    default: false,
+                },
+                None => expander.expand_type(original_name, required, value),
+            };
+            fields.push(Field {
+                property: property.clone(),
+                ident: field_ident(property, expander.options.identifier_case),
+                default: field_type.default
+                    || field_type.typ.starts_with("Option<")
+                    || expander
+                        .defaults
+                        .contains(without_lifetime(&field_type.typ)),
+                typ: field_type.typ.split_whitespace().collect(),
+            });
+        }
+        Kind::Struct(fields)
+    } else if def.enum_.as_ref().is_some_and(|e| !e.is_empty()) {
+        let values = def.enum_.as_deref().unwrap_or_default();
+        if values.iter().any(Value::is_null) {
+            // `null` makes the type an alias of an `Option` of the enum
+            return Kind::Opaque;
+        }
+        let variants = enum_variant_names(def)
+            .into_iter()
+            .zip(values)
+            .filter_map(|(variant, value)| Some((value.clone(), variant_ident(&variant?))));
+        Kind::Enum(variants.collect())
+    } else if expander.is_string_newtype(def) {
+        Kind::Opaque
+    } else {
+        expander.current_field.clear();
+        let typ = expander.expand_type("", true, def).typ;
+        Kind::Alias(typ.split_whitespace().collect())
+    }
+}
+
+/// Whether the conversion of `v` leaves it as it is.
+fn is_identity(conversion: &TokenStream) -> bool {
+    conversion.to_string() == "v"
+}
+
+struct Converter<'v> {
+    from: &'v Version,
+    to: &'v Version,
+    matching: FieldMatching,
+    /// The types which have a `From` implementation.
+    converted: BTreeSet<String>,
+}
+
+impl Converter<'_> {
+    /// Whether the type `typ` refers to a type generated from either schema.
+    fn is_generated(&self, typ: &str) -> bool {
+        // Paths such as `serde_json::Value` are not generated
+        typ.split(|c: char| "<>,()[];&".contains(c))
+            .filter(|path| !path.contains("::"))
+            .any(|name| self.from.kinds.contains_key(name) || self.to.kinds.contains_key(name))
+    }
+
+    /// Converts `expr` of the type `from` of the old version to the type `to`
+    /// of the new version.
+    fn convert(&self, expr: TokenStream, from: &str, to: &str) -> Option<TokenStream> {
+        let from = self.from.resolve(from);
+        let to = self.to.resolve(to);
+        let wrapped = |prefix: &str| {
+            Some((
+                from.strip_prefix(prefix)?.strip_suffix('>')?,
+                to.strip_prefix(prefix)?.strip_suffix('>')?,
+            ))
+        };
+        if let Some((from, to)) = wrapped("Option<") {
+            let inner = self.convert(quote!(v), from, to)?;
+            if is_identity(&inner) {
+                return Some(expr);
+            }
+            return Some(quote!(#expr.map(|v| #inner)));
+        }
+        if let Some(to) = to.strip_prefix("Option<").and_then(|t| t.strip_suffix('>')) {
+            let inner = self.convert(expr, from, to)?;
+            return Some(quote!(Some(#inner)));
+        }
+        for prefix in &["Vec<", "::std::collections::BTreeSet<"] {
+            if let Some((from, to)) = wrapped(prefix) {
+                let inner = self.convert(quote!(v), from, to)?;
+                if is_identity(&inner) {
+                    return Some(expr);
+                }
+                return Some(quote!(#expr.into_iter().map(|v| #inner).collect()));
+            }
+        }
+        for prefix in &[
+            "::std::collections::BTreeMap<String,",
+            "::schemafy_core::IndexMap<String,",
+        ] {
+            if let Some((from, to)) = wrapped(prefix) {
+                let inner = self.convert(quote!(v), from, to)?;
+                if is_identity(&inner) {
+                    return Some(expr);
+                }
+                return Some(quote!(#expr.into_iter().map(|(k, v)| (k, #inner)).collect()));
+            }
+        }
+        if let Some((from, to)) = wrapped("Box<") {
+            let inner = self.convert(quote!(v), from, to)?;
+            if is_identity(&inner) {
+                return Some(expr);
+            }
+            let inner = self.convert(quote!((*#expr)), from, to)?;
+            return Some(quote!(Box::new(#inner)));
+        }
+        if from != to {
+            None
+        } else if self.converted.contains(from) {
+            Some(quote!(#expr.into()))
+        } else if self.is_generated(from) {
+            // Each version has its own type of that name
+            None
+        } else {
+            Some(expr)
+        }
+    }
+
+    fn expand(&self, name: &str) -> Option<TokenStream> {
+        let from_type = self.from.path(name);
+        let to_type = self.to.path(name);
+        let body = match (&self.from.kinds[name], &self.to.kinds[name]) {
+            (Kind::Struct(from), Kind::Struct(to)) => {
+                let fields = to
+                    .iter()
+                    .map(|to_field| {
+                        let ident = &to_field.ident;
+                        let from_field = from.iter().find(|from_field| match self.matching {
+                            FieldMatching::PropertyName => from_field.property == to_field.property,
+                            FieldMatching::FieldName => from_field.ident == to_field.ident,
+                        });
+                        let value = match from_field {
+                            Some(from_field) => {
+                                let from_ident = &from_field.ident;
+                                self.convert(
+                                    quote!(value.#from_ident),
+                                    &from_field.typ,
+                                    &to_field.typ,
+                                )?
+                            }
+                            // New fields can only be filled in if they are optional
+                            None if to_field.default => quote!(Default::default()),
+                            None => return None,
+                        };
+                        Some(quote!(#ident: #value))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                quote!(#to_type { #(#fields),* })
+            }
+            (Kind::Enum(from), Kind::Enum(to)) => {
+                let arms = from
+                    .iter()
+                    .map(|(value, from_variant)| {
+                        let (_, to_variant) = to.iter().find(|(v, _)| v == value)?;
+                        Some(quote!(#from_type::#from_variant => #to_type::#to_variant))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                quote!(match value { #(#arms),* })
+            }
+            _ => return None,
+        };
+        Some(quote! {
+            impl From<#from_type> for #to_type {
+                fn from(value: #from_type) -> Self {
+                    #body
+                }
+            }
+        })
+    }
+}
+
+/// Generates `From` implementations which convert the types generated from
+/// the schema `from` into the types of the same name generated from the
+/// schema `to`, such as two versions of the same schema.
+///
+/// The types of each schema are expected to be in the module `from_path`
+/// and `to_path` respectively, e.g. `"v1::"`. Fields are moved to the field
+/// which they match, converting their type if needed, while the fields
+/// which only exist in `to` are filled in with `Default::default()`. No
+/// conversion is generated for a type if a field which only exists in `to`
+/// is required, if the fields have incompatible types or if it is not a
+/// struct or a unit enum from `enum`, as well as for the types which only
+/// exist in `to`.
+pub fn expand_conversions(
+    root_name: Option<&str>,
+    from_path: &str,
+    from: &Schema,
+    to_path: &str,
+    to: &Schema,
+    matching: FieldMatching,
+) -> TokenStream {
+    let from = Version::new(root_name, from_path, from);
+    let to = Version::new(root_name, to_path, to);
+    let mut converter = Converter {
+        converted: from
+            .kinds
+            .iter()
+            .filter(|(name, kind)| {
+                matches!(kind, Kind::Struct(_) | Kind::Enum(_)) && to.kinds.contains_key(*name)
+            })
+            .map(|(name, _)| name.clone())
+            .collect(),
+        from: &from,
+        to: &to,
+        matching,
+    };
+    // Types which can't be converted make the types containing them
+    // impossible to convert as well
+    loop {
+        let convertible = converter
+            .converted
+            .iter()
+            .filter(|name| converter.expand(name).is_some())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if convertible.len() == converter.converted.len() {
+            break;
+        }
+        converter.converted = convertible;
+    }
+    let impls = converter
+        .converted
+        .iter()
+        .filter_map(|name| converter.expand(name));
+    quote!(#(#impls)*)
+}
@@ -0,0 +1,42 @@
+use crate::Schema;
+
+/// A version of JSON Schema, as declared by the `$schema` of a schema.
+///
+/// The drafts are ordered by their release so that keywords which changed
+/// meaning can be checked with comparisons such as
+/// `draft >= Draft::Draft201909`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Draft {
+    Draft04,
+    Draft06,
+    Draft07,
+    Draft201909,
+    Draft202012,
+}
+
+impl Draft {
+    /// The draft of the meta-schema URI `uri`, such as
+    /// `http://json-schema.org/draft-07/schema#`, if it is one of the
+    /// meta-schemas of JSON Schema.
+    pub fn from_uri(uri: &str) -> Option<Draft> {
+        let path = uri
+            .trim_end_matches('#')
+            .trim_start_matches("http://")
+            .trim_start_matches("https://")
+            .strip_prefix("json-schema.org/")?;
+        match path {
+            "draft-04/schema" => Some(Draft::Draft04),
+            "draft-06/schema" => Some(Draft::Draft06),
+            "draft-07/schema" => Some(Draft::Draft07),
+            "draft/2019-09/schema" => Some(Draft::Draft201909),
+            "draft/2020-12/schema" => Some(Draft::Draft202012),
+            _ => None,
+        }
+    }
+
+    /// The draft which the top-level `$schema` of `schema` declares, `None`
+    /// if it has none or it is not a known meta-schema.
+    pub fn of(schema: &Schema) -> Option<Draft> {
+        schema.schema.as_deref().and_then(Draft::from_uri)
+    }
+}
@@ -0,0 +1,66 @@
+use std::{error::Error, fmt, io};
+
+/// The errors of generating Rust types from a schema.
+#[derive(Debug)]
+pub enum SchemafyError {
+    /// The schema could not be read or the output could not be written.
+    Io(io::Error),
+    /// The schema is not valid JSON (or YAML) or not shaped like a schema.
+    Parse(String),
+    /// A `$ref` which does not point to a subschema of the schema.
+    UnresolvedRef(String),
+    /// A part of the schema which can not be turned into Rust types.
+    Unsupported(String),
+    /// `rustfmt` failed to format the output, with its error output.
+    Rustfmt(String),
+    /// Different parts of the schema would get the same Rust name.
+    IdentifierCollision(String),
+    /// The `examples` which do not fit the types generated for them, with
+    /// `check_examples` or `example_fixtures`.
+    ExampleMismatch(Vec<String>),
+}
+
+impl fmt::Display for SchemafyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemafyError::Io(err) => err.fmt(f),
+            SchemafyError::Parse(message) | SchemafyError::Unsupported(message) => {
+                f.write_str(message)
+            }
+            SchemafyError::UnresolvedRef(ref_) => write!(f, "Unresolved `$ref`: `{}`", ref_),
+            SchemafyError::Rustfmt(stderr) => write!(f, "rustfmt failed: {}", stderr),
+            SchemafyError::IdentifierCollision(name) => {
+                write!(f, "Several parts of the schema are named `{}`", name)
+            }
+            SchemafyError::ExampleMismatch(mismatches) => f.write_str(&mismatches.join("\n")),
+        }
+    }
+}
+
+impl Error for SchemafyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SchemafyError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SchemafyError {
+    fn from(err: io::Error) -> SchemafyError {
+        SchemafyError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SchemafyError {
+    fn from(err: serde_json::Error) -> SchemafyError {
+        SchemafyError::Parse(err.to_string())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for SchemafyError {
+    fn from(err: serde_yaml::Error) -> SchemafyError {
+        SchemafyError::Parse(err.to_string())
+    }
+}
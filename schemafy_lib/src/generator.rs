@@ -1,5 +1,9 @@
-use crate::Expander;
+use crate::{
+    bundle::Document, Expander, GenerationReport, IdentifierCase, OneOfTagging, Schema,
+    SchemaVisitor, SchemafyError,
+};
 use std::{
+    collections::BTreeMap,
     io,
     path::{Path, PathBuf},
 };
@@ -21,8 +25,253 @@ pub struct Generator<'a, 'b> {
     /// re-exported this crate or imported it under a different name,
     /// the default should be fine.
     pub schemafy_path: &'a str,
-    /// The JSON schema file to read
+    /// The JSON schema file to read. Files ending in `.yaml` or `.yml` are
+    /// read as YAML, which requires the `yaml` feature.
     pub input_file: &'b Path,
+    /// Extra arguments for the `rustfmt` which formats the output of
+    /// `generate_to_file` and `generate_to_dir`, e.g. `--edition 2021` or
+    /// `--config-path path/to/rustfmt.toml`.
+    pub rustfmt_args: Vec<String>,
+    /// How `generate_to_file` and `generate_to_dir` format their output.
+    pub formatter: Formatter,
+    /// The options of the generated code, which the [`Expander`] borrows.
+    pub options: Options<'a>,
+}
+
+/// How [`Generator::generate_to_file`] and [`Generator::generate_to_dir`]
+/// format their output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Formatter {
+    /// Runs the `rustfmt` binary with the `rustfmt_args` on the written files.
+    Rustfmt,
+    /// Formats in process, without requiring `rustfmt` to be installed.
+    #[cfg(feature = "prettyplease")]
+    Prettyplease,
+    /// Leaves the output unformatted.
+    None,
+}
+
+/// The options of the code generated by a [`Generator`], which are set
+/// through its [`GeneratorBuilder`].
+#[derive(Debug, PartialEq)]
+pub struct Options<'a> {
+    /// Generate newtypes which check `minLength`/`maxLength` when they are
+    /// deserialized instead of plain `String`s.
+    pub string_length_validation: bool,
+    /// Generate an untagged enum with a variant per type for `type` arrays
+    /// such as `["string", "integer"]` instead of `serde_json::Value`.
+    pub untagged_type_unions: bool,
+    /// Add `pub const SCHEMA: &str` to each generated type, containing the JSON
+    /// of the subschema it was generated from.
+    pub schema_constants: bool,
+    /// Prepended to the name of every generated type, e.g. `Api` turns
+    /// `Foo` into `ApiFoo`. This avoids collisions when types from several
+    /// schemas are generated into the same module. The serialized names are
+    /// not affected.
+    pub type_prefix: String,
+    /// Appended to the name of every generated type, like `type_prefix`.
+    pub type_suffix: String,
+    /// The smart pointer which breaks the infinite size of types that
+    /// contain themselves, e.g. `std::rc::Rc` or `std::sync::Arc` for
+    /// trees which are shared. Deserializing those requires the `rc`
+    /// feature of serde.
+    pub recursion_pointer: syn::Path,
+    /// The path of the `serde_json` crate in the generated code, used for
+    /// `serde_json::Value` and the `json_from_str` implementations.
+    pub serde_json_path: String,
+    /// The path of the `serde` crate in the generated code, e.g.
+    /// `crate::reexports::serde`, for crates which do not depend on `serde`
+    /// directly. The derives are given by this path together with
+    /// `#[serde(crate = "...")]`, apart from those of `serde_repr` which
+    /// require `serde` itself.
+    pub serde_path: String,
+    /// Wraps the generated code in `pub mod` of this name unless empty, which
+    /// imports everything of its parent module like the output of
+    /// `generate_to_dir`, which ignores this option. `module_doc` then
+    /// documents that module, which also works for the output of a macro.
+    pub output_module: String,
+    /// How the names of the schema become identifiers. The types named after a
+    /// property, such as of inline objects, are always PascalCase.
+    pub identifier_case: IdentifierCase,
+    /// The modules of the types of the schemas which `$ref`s point into, keyed
+    /// by the document, such as `common.json` to `crate::common` so that a `$ref`
+    /// to `common.json#/definitions/address` becomes `crate::common::Address`.
+    /// The types of those documents are generated separately, e.g. by another
+    /// `schemafy!`, and are not resolved in this schema.
+    pub ref_modules: BTreeMap<String, syn::Path>,
+    /// The derives of every generated type besides the serde ones and `Default`.
+    /// Types with fields which do not implement `Clone` or `PartialEq` may
+    /// need fewer. Enums of unit variants also derive `Hash`, and `Eq` if they
+    /// derive `PartialEq`.
+    pub base_derives: Vec<syn::Path>,
+    /// How a `oneOf` is represented.
+    pub one_of_tagging: OneOfTagging,
+    /// Add `#[serde(default)]` to structs whose fields all have a default so
+    /// that missing fields are filled in, e.g. `{}` deserializes to `Default::default()`.
+    pub serde_default_container: bool,
+    /// Generate a `validate` method on structs with `dependencies` or
+    /// `dependentRequired` which checks that the required properties are present.
+    pub dependency_validation: bool,
+    /// Use `OrderedFloat<f64>` (re-exported by `schemafy_core` with its
+    /// `ordered-float` feature) instead of `f64` for numbers so that they
+    /// implement `Eq`, `Ord` and `Hash`.
+    pub ordered_floats: bool,
+    /// Fail the generation if any of the `examples` of a schema do not fit the
+    /// generated type, e.g. a string example for an `integer`.
+    pub check_examples: bool,
+    /// Keep the fields of structs in the order of the properties in the
+    /// schema instead of sorting them, and use `IndexMap` (re-exported by
+    /// `schemafy_core` with its `indexmap` feature) for objects.
+    pub preserve_order: bool,
+    /// Deserialize strings as `&'a str` borrowed from the input instead of
+    /// `String`, adding a lifetime to every type which contains one. Strings
+    /// with escape sequences can not be borrowed and fail to deserialize.
+    pub borrow: bool,
+    /// Generate a `validate` method on structs which checks the constraints
+    /// that the types can not express, such as `minimum`, `maxLength`,
+    /// `multipleOf`, `dependencies` and `not`, including those of nested
+    /// types. It replaces the method of `dependency_validation`. Checking
+    /// `pattern` requires the `regex` feature of `schemafy_core`. Without this
+    /// option `not` is ignored.
+    pub validation: bool,
+    /// Generate `BTreeSet`s instead of `Vec`s for arrays with `uniqueItems` whose
+    /// items are strings, integers or booleans (or `OrderedFloat`s with
+    /// `ordered_floats`). Arrays of other items stay `Vec`s.
+    pub unique_item_sets: bool,
+    /// Wrap string properties with `format: password` or `writeOnly` in
+    /// `Secret` (from `schemafy_core`), which hides them from `Debug`, and skip
+    /// them when serializing.
+    pub redact_secrets: bool,
+    /// Add associated constants with the constraints of the schema, `ALLOWED`
+    /// with the values of enums and `MIN_LENGTH`/`MAX_LENGTH` on the newtypes of
+    /// `string_length_validation`.
+    pub bound_constants: bool,
+    /// Add a "See also" line with an intra-doc link to the documentation of
+    /// fields which refer to another generated type through `$ref`.
+    pub doc_links: bool,
+    /// Replace the derived `Debug` of structs with a `writeOnly` or `format:
+    /// password` property by one printing `[redacted]` for those properties.
+    pub redact_debug: bool,
+    /// Generate newtypes which check `minProperties`/`maxProperties` when they
+    /// are deserialized instead of plain maps. They can be iterated like the
+    /// map but only built through `new`, which checks the number of entries.
+    pub map_size_validation: bool,
+    /// Sort the variants of enums generated from `enum` by their name instead
+    /// of following the schema. The variants of untagged enums, such as from
+    /// `oneOf`, are tried in order and so always keep the order of the schema.
+    pub sort_enum_variants: bool,
+    /// Implements `FromStr` for the generated structs, parsing a JSON document
+    /// with `serde_json::from_str`.
+    pub json_from_str: bool,
+    /// Starts the output with the `title` and `description` of the schema as
+    /// module documentation (`//!`). Only for output which is a module of its
+    /// own, such as of `generate_to_file` or with `output_module`, since a
+    /// macro can not expand to inner doc comments.
+    pub module_doc: bool,
+    /// Replace the derived `PartialEq` of structs with a `readOnly` property by
+    /// one which ignores those properties, e.g. to compare an object sent to a
+    /// server with the one it returned. A derived `Hash` is then inconsistent
+    /// with `PartialEq`.
+    pub eq_ignore_read_only: bool,
+    /// Generates typed strings for the `format`s `email`, `hostname` and `uri`
+    /// (the validated `schemafy_core` newtypes `Email`, `Hostname` and `Uri`)
+    /// and for `ipv4` and `ipv6` (`std::net::Ipv4Addr` and `Ipv6Addr`).
+    /// Other formats remain `String`.
+    pub string_formats: bool,
+    /// Skip serializing empty `Vec`s, sets and maps of fields with
+    /// `#[serde(default)]`, which deserialize to the same empty collection.
+    pub skip_empty_collections: bool,
+    /// Generate an untagged enum instead of `serde_json::Value` for an `anyOf`
+    /// which is not a value or an array of it, with a variant for each `$ref` and
+    /// scalar alternative in the order of the `anyOf` and a final
+    /// `Other(serde_json::Value)` for anything else. `type` arrays become enums
+    /// as with `untagged_type_unions`.
+    pub untagged_fallbacks: bool,
+    /// Generates `apply_patch` for the structs, applying a JSON Merge Patch
+    /// (RFC 7386) where a `null` member removes an optional field. The patch is
+    /// applied to the struct serialized with `serde_json`, so the fields which
+    /// are skipped when serializing, such as redacted secrets, do not survive it.
+    pub merge_patch: bool,
+    /// Called while generating the structs and their fields to add attributes
+    /// of its own to them.
+    pub visitor: Option<&'a dyn SchemaVisitor>,
+    /// Generates `i64` and `u64` for strings with the `int64` and `uint64` formats,
+    /// which some APIs use for integers beyond the precision of JavaScript numbers.
+    /// The integers are still serialized as strings.
+    pub string_integers: bool,
+    /// Generates maps keyed by an enum for the objects whose `propertyNames` is an
+    /// enum of strings, instead of maps keyed by `String`. The enums derive `Ord`
+    /// so that they can be the keys of a `BTreeMap`.
+    pub typed_map_keys: bool,
+    /// Boxes the variants of `oneOf` enums which hold a struct, so that the small
+    /// variants do not take the space of the largest one, as `clippy::large_enum_variant`
+    /// warns about. The variants which are objects of their own are generated as
+    /// structs to be boxed instead of struct variants.
+    pub box_variants: bool,
+    /// Generates `example_0()`, `example_1()`... constructors of the structs and
+    /// enums with `examples` for use in tests, built from the examples under
+    /// `#[cfg(test)]`. Fails if one of those examples does not fit the generated
+    /// type, as with `check_examples`.
+    pub example_fixtures: bool,
+    /// Implements `PartialEq<str>` and `PartialEq<&str>` for the enums of strings,
+    /// comparing a variant with the string it is serialized as.
+    pub str_eq: bool,
+    /// Generate `chrono::DateTime<Utc>` (re-exported by `schemafy_core` with its
+    /// `chrono` feature) instead of `i64` for integers with `format: unix-time`,
+    /// serialized as the seconds since the epoch.
+    pub unix_timestamps: bool,
+}
+
+impl<'a> Default for Options<'a> {
+    fn default() -> Self {
+        Options {
+            string_length_validation: false,
+            untagged_type_unions: false,
+            schema_constants: false,
+            type_prefix: String::new(),
+            type_suffix: String::new(),
+            recursion_pointer: syn::parse_quote!(Box),
+            serde_json_path: "serde_json".to_string(),
+            serde_path: "serde".to_string(),
+            output_module: String::new(),
+            identifier_case: IdentifierCase::Rust,
+            ref_modules: BTreeMap::new(),
+            base_derives: vec![
+                syn::parse_quote!(Clone),
+                syn::parse_quote!(PartialEq),
+                syn::parse_quote!(Debug),
+            ],
+            one_of_tagging: OneOfTagging::Untagged,
+            serde_default_container: false,
+            dependency_validation: false,
+            ordered_floats: false,
+            check_examples: false,
+            preserve_order: false,
+            borrow: false,
+            validation: false,
+            unique_item_sets: false,
+            redact_secrets: false,
+            bound_constants: false,
+            doc_links: false,
+            redact_debug: false,
+            map_size_validation: false,
+            sort_enum_variants: false,
+            json_from_str: false,
+            module_doc: false,
+            eq_ignore_read_only: false,
+            string_formats: false,
+            skip_empty_collections: false,
+            untagged_fallbacks: false,
+            merge_patch: false,
+            visitor: None,
+            string_integers: false,
+            typed_map_keys: false,
+            box_variants: false,
+            example_fixtures: false,
+            str_eq: false,
+            unix_timestamps: false,
+        }
+    }
 }
 
 impl<'a, 'b> Generator<'a, 'b> {
@@ -31,38 +280,229 @@ impl<'a, 'b> Generator<'a, 'b> {
         GeneratorBuilder::default()
     }
 
+    /// Generate the Rust types for the schema.
+    ///
+    /// The output only depends on the schema and the options, so generating
+    /// twice from the same input yields byte-for-byte identical code which
+    /// can be checked in and diffed.
     pub fn generate(&self) -> proc_macro2::TokenStream {
-        let input_file = if self.input_file.is_relative() {
-            let crate_root = get_crate_root().unwrap();
-            crate_root.join(self.input_file)
-        } else {
-            PathBuf::from(self.input_file)
-        };
+        self.try_generate().unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        let json = std::fs::read_to_string(&input_file).unwrap_or_else(|err| {
-            panic!("Unable to read `{}`: {}", input_file.to_string_lossy(), err)
-        });
+    /// Like [`generate`](Self::generate) but returns an error instead of
+    /// panicking if the schema can not be read, parsed or resolved.
+    pub fn try_generate(&self) -> Result<proc_macro2::TokenStream, SchemafyError> {
+        self.try_generate_schema(&self.read_input_file()?)
+    }
 
-        let schema = serde_json::from_str(&json).unwrap_or_else(|err| {
-            panic!(
-                "Cannot parse `{}` as JSON: {}",
-                input_file.to_string_lossy(),
-                err
-            )
-        });
-        let mut expander = Expander::new(self.root_name.as_deref(), self.schemafy_path, &schema);
-        expander.expand(&schema)
+    fn read_input_file(&self) -> Result<Schema, SchemafyError> {
+        let (input_file, contents) = read_file(self.input_file)?;
+        parse_file(&input_file, &contents)
+    }
+
+    /// Generates the types of several schema files which refer to each other,
+    /// by file name or by `$id`, once for all of them. `root_name` and
+    /// `input_file` are ignored, the root of each file is named after the file
+    /// unless it only holds definitions.
+    ///
+    /// Definitions which are in several files are generated once, as long as
+    /// the files agree on them. Otherwise the name is reported as a
+    /// `SchemafyError::IdentifierCollision`.
+    pub fn generate_many<P: AsRef<Path>>(
+        &self,
+        input_files: &[P],
+    ) -> Result<proc_macro2::TokenStream, SchemafyError> {
+        let documents = input_files
+            .iter()
+            .map(|input_file| {
+                let (input_file, contents) = read_file(input_file.as_ref())?;
+                let schema = parse_file(&input_file, &contents)?;
+                Ok(Document {
+                    file_name: input_file
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into_owned(),
+                    id: schema.id.clone().or_else(|| document_id(&contents)),
+                    schema,
+                })
+            })
+            .collect::<Result<Vec<_>, SchemafyError>>()?;
+        let schema = crate::bundle::bundle(&documents)?;
+        let mut expander = self.expander(&schema);
+        expander.root_name = None;
+        expander.check_refs(&schema)?;
+        expander.check_all_of(&schema)?;
+        expander.check_one_of_tagging(&schema)?;
+        expander.try_expand(&schema)
+    }
+
+    /// Generate the Rust types for an already parsed schema, ignoring
+    /// `input_file`.
+    pub fn generate_schema(&self, schema: &Schema) -> proc_macro2::TokenStream {
+        self.expander(schema).expand(schema)
+    }
+
+    /// Like [`generate_schema`](Self::generate_schema) but appends the types
+    /// to `tokens`, so a procedural macro built on schemafy can splice them
+    /// into the code it emits.
+    pub fn generate_schema_into(&self, schema: &Schema, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(self.generate_schema(schema));
+    }
+
+    /// Like [`generate_schema`](Self::generate_schema) but returns an error
+    /// instead of panicking if a `$ref` of the schema can not be resolved.
+    pub fn try_generate_schema(
+        &self,
+        schema: &Schema,
+    ) -> Result<proc_macro2::TokenStream, SchemafyError> {
+        let mut expander = self.expander(schema);
+        expander.check_refs(schema)?;
+        expander.check_all_of(schema)?;
+        expander.check_one_of_tagging(schema)?;
+        expander.try_expand(schema)
+    }
+
+    /// Like [`generate_schema`](Self::generate_schema) but also returns the
+    /// `$comment`s of the schema, keyed by the JSON pointer of the subschema
+    /// they were found in. The comments never appear in the generated code.
+    pub fn generate_schema_with_comments(
+        &self,
+        schema: &Schema,
+    ) -> (proc_macro2::TokenStream, Vec<(String, String)>) {
+        let mut expander = self.expander(schema);
+        let tokens = expander.expand(schema);
+        (tokens, expander.comments().to_vec())
+    }
+
+    /// Like [`generate_schema`](Self::generate_schema) but also returns the
+    /// counts of the generated types and fields, including the fields which
+    /// fell back to `serde_json::Value`.
+    pub fn generate_schema_with_report(
+        &self,
+        schema: &Schema,
+    ) -> (proc_macro2::TokenStream, GenerationReport) {
+        let mut expander = self.expander(schema);
+        let tokens = expander.expand(schema);
+        (tokens, expander.report())
+    }
+
+    /// Generate the Rust types for an already parsed schema, keyed by the
+    /// name of the type.
+    pub fn generate_types(&self, schema: &Schema) -> BTreeMap<String, proc_macro2::TokenStream> {
+        self.expander(schema).expand_types(schema)
+    }
+
+    /// Hashes the generated code of each type in the schema.
+    ///
+    /// The hashes are stable across runs and compiler versions so a build
+    /// script can store them and use [`changed_types`] to decide whether
+    /// it needs to regenerate anything.
+    pub fn type_hashes(&self, schema: &Schema) -> BTreeMap<String, u64> {
+        let mut expander = self.expander(schema);
+        expander.expand(schema);
+        expander.type_hashes()
+    }
+
+    fn expander<'s>(&'s self, schema: &'s Schema) -> Expander<'s> {
+        Expander::with_options(
+            self.root_name.as_deref(),
+            self.schemafy_path,
+            schema,
+            &self.options,
+        )
+    }
+
+    pub fn generate_to_file<P: ?Sized + AsRef<Path>>(
+        &self,
+        output_file: &'b P,
+    ) -> Result<(), SchemafyError> {
+        let tokens = self.try_generate()?;
+        std::fs::write(output_file, self.format(tokens)?)?;
+        self.rustfmt(output_file.as_ref())
     }
 
-    pub fn generate_to_file<P: ?Sized + AsRef<Path>>(&self, output_file: &'b P) -> io::Result<()> {
-        use std::process::Command;
-        let tokens = self.generate();
-        let out = tokens.to_string();
-        std::fs::write(output_file, &out)?;
-        Command::new("rustfmt")
-            .arg(output_file.as_ref().as_os_str())
+    /// The source of `tokens`, formatted in process by the `prettyplease`
+    /// formatter. The `rustfmt` formatter formats the written files instead.
+    fn format(&self, tokens: proc_macro2::TokenStream) -> Result<String, SchemafyError> {
+        match self.formatter {
+            Formatter::Rustfmt | Formatter::None => Ok(tokens.to_string()),
+            #[cfg(feature = "prettyplease")]
+            Formatter::Prettyplease => {
+                let file = syn::parse2::<syn::File>(tokens).map_err(|err| {
+                    SchemafyError::Parse(format!("The output is not valid Rust: {}", err))
+                })?;
+                Ok(prettyplease::unparse(&file))
+            }
+        }
+    }
+
+    fn rustfmt(&self, file: &Path) -> Result<(), SchemafyError> {
+        if self.formatter != Formatter::Rustfmt {
+            return Ok(());
+        }
+        let output = std::process::Command::new("rustfmt")
+            .args(&self.rustfmt_args)
+            .arg(file.as_os_str())
             .output()?;
-        Ok(())
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(SchemafyError::Rustfmt(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ))
+        }
+    }
+
+    /// Like [`generate_to_file`](Self::generate_to_file) but writes each type
+    /// into its own file in `output_dir`, along with a `mod.rs` which declares
+    /// the files as modules and re-exports their types.
+    ///
+    /// Every file imports everything of its parent module, which is how the
+    /// types refer to each other. As with the output of `generate_to_file`,
+    /// the serde derives need to be in scope of the module including `mod.rs`.
+    pub fn generate_to_dir<P: ?Sized + AsRef<Path>>(
+        &self,
+        output_dir: &P,
+    ) -> Result<(), SchemafyError> {
+        use inflector::Inflector;
+
+        let schema = self.read_input_file()?;
+        let mut expander = self.expander(&schema);
+        expander.check_refs(&schema)?;
+        expander.check_all_of(&schema)?;
+        expander.check_one_of_tagging(&schema)?;
+        // Types whose names only differ in case share a module
+        let mut modules = BTreeMap::<String, proc_macro2::TokenStream>::new();
+        for (name, tokens) in expander.try_expand_types(&schema)? {
+            modules
+                .entry(crate::str_to_ident(&name.to_snake_case()).to_string())
+                .or_default()
+                .extend(tokens);
+        }
+
+        let output_dir = output_dir.as_ref();
+        std::fs::create_dir_all(output_dir)?;
+        let mut mod_rs = crate::module_doc(&schema)
+            .filter(|_| self.options.module_doc)
+            .unwrap_or_default();
+        mod_rs.push_str("#[allow(unused_imports)]\nuse super::*;\n");
+        for (module, tokens) in &modules {
+            let contents = quote! {
+                #[allow(unused_imports)]
+                use super::*;
+                #tokens
+            };
+            std::fs::write(
+                output_dir.join(format!("{}.rs", module)),
+                self.format(contents)?,
+            )?;
+            mod_rs.push_str(&format!("mod {0};\npub use self::{0}::*;\n", module));
+        }
+        let mod_file = output_dir.join("mod.rs");
+        std::fs::write(&mod_file, mod_rs)?;
+        // rustfmt follows the `mod` declarations of `mod.rs` into the other files
+        self.rustfmt(&mod_file)
     }
 }
 
@@ -79,6 +519,9 @@ impl<'a, 'b> Default for GeneratorBuilder<'a, 'b> {
                 root_name: None,
                 schemafy_path: "::schemafy_core::",
                 input_file: Path::new("schema.json"),
+                rustfmt_args: Vec::new(),
+                formatter: Formatter::Rustfmt,
+                options: Options::default(),
             },
         }
     }
@@ -97,15 +540,276 @@ impl<'a, 'b> GeneratorBuilder<'a, 'b> {
         self.inner.input_file = input_file.as_ref();
         self
     }
+    pub fn with_rustfmt_args<I, S>(mut self, rustfmt_args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.inner.rustfmt_args = rustfmt_args.into_iter().map(Into::into).collect();
+        self
+    }
+    pub fn with_formatter(mut self, formatter: Formatter) -> Self {
+        self.inner.formatter = formatter;
+        self
+    }
     pub fn with_schemafy_path(mut self, schemafy_path: &'a str) -> Self {
         self.inner.schemafy_path = schemafy_path;
         self
     }
+    /// Replaces all the options of the generated code at once, e.g. with options
+    /// which are parsed elsewhere.
+    pub fn with_options(mut self, options: Options<'a>) -> Self {
+        self.inner.options = options;
+        self
+    }
+    pub fn with_string_length_validation(mut self, string_length_validation: bool) -> Self {
+        self.inner.options.string_length_validation = string_length_validation;
+        self
+    }
+    pub fn with_untagged_type_unions(mut self, untagged_type_unions: bool) -> Self {
+        self.inner.options.untagged_type_unions = untagged_type_unions;
+        self
+    }
+    pub fn with_schema_constants(mut self, schema_constants: bool) -> Self {
+        self.inner.options.schema_constants = schema_constants;
+        self
+    }
+    pub fn with_type_prefix(mut self, type_prefix: &str) -> Self {
+        self.inner.options.type_prefix = type_prefix.to_string();
+        self
+    }
+    pub fn with_type_suffix(mut self, type_suffix: &str) -> Self {
+        self.inner.options.type_suffix = type_suffix.to_string();
+        self
+    }
+    pub fn with_recursion_pointer(mut self, recursion_pointer: syn::Path) -> Self {
+        self.inner.options.recursion_pointer = recursion_pointer;
+        self
+    }
+    pub fn with_serde_json_path(mut self, serde_json_path: &str) -> Self {
+        self.inner.options.serde_json_path = serde_json_path.to_string();
+        self
+    }
+    pub fn with_serde_path(mut self, serde_path: &str) -> Self {
+        self.inner.options.serde_path = serde_path.to_string();
+        self
+    }
+    pub fn with_output_module(mut self, output_module: &str) -> Self {
+        self.inner.options.output_module = output_module.to_string();
+        self
+    }
+    pub fn with_identifier_case(mut self, identifier_case: IdentifierCase) -> Self {
+        self.inner.options.identifier_case = identifier_case;
+        self
+    }
+    pub fn with_ref_modules(mut self, ref_modules: BTreeMap<String, syn::Path>) -> Self {
+        self.inner.options.ref_modules = ref_modules;
+        self
+    }
+    pub fn with_base_derives(mut self, base_derives: Vec<syn::Path>) -> Self {
+        self.inner.options.base_derives = base_derives;
+        self
+    }
+    pub fn with_one_of_tagging(mut self, one_of_tagging: OneOfTagging) -> Self {
+        self.inner.options.one_of_tagging = one_of_tagging;
+        self
+    }
+    pub fn with_serde_default_container(mut self, serde_default_container: bool) -> Self {
+        self.inner.options.serde_default_container = serde_default_container;
+        self
+    }
+    pub fn with_dependency_validation(mut self, dependency_validation: bool) -> Self {
+        self.inner.options.dependency_validation = dependency_validation;
+        self
+    }
+    pub fn with_ordered_floats(mut self, ordered_floats: bool) -> Self {
+        self.inner.options.ordered_floats = ordered_floats;
+        self
+    }
+    pub fn with_check_examples(mut self, check_examples: bool) -> Self {
+        self.inner.options.check_examples = check_examples;
+        self
+    }
+    pub fn with_preserve_order(mut self, preserve_order: bool) -> Self {
+        self.inner.options.preserve_order = preserve_order;
+        self
+    }
+    pub fn with_borrow(mut self, borrow: bool) -> Self {
+        self.inner.options.borrow = borrow;
+        self
+    }
+    pub fn with_validation(mut self, validation: bool) -> Self {
+        self.inner.options.validation = validation;
+        self
+    }
+    pub fn with_unique_item_sets(mut self, unique_item_sets: bool) -> Self {
+        self.inner.options.unique_item_sets = unique_item_sets;
+        self
+    }
+    pub fn with_redact_secrets(mut self, redact_secrets: bool) -> Self {
+        self.inner.options.redact_secrets = redact_secrets;
+        self
+    }
+    pub fn with_bound_constants(mut self, bound_constants: bool) -> Self {
+        self.inner.options.bound_constants = bound_constants;
+        self
+    }
+    pub fn with_doc_links(mut self, doc_links: bool) -> Self {
+        self.inner.options.doc_links = doc_links;
+        self
+    }
+    pub fn with_redact_debug(mut self, redact_debug: bool) -> Self {
+        self.inner.options.redact_debug = redact_debug;
+        self
+    }
+    pub fn with_map_size_validation(mut self, map_size_validation: bool) -> Self {
+        self.inner.options.map_size_validation = map_size_validation;
+        self
+    }
+    pub fn with_sort_enum_variants(mut self, sort_enum_variants: bool) -> Self {
+        self.inner.options.sort_enum_variants = sort_enum_variants;
+        self
+    }
+    pub fn with_json_from_str(mut self, json_from_str: bool) -> Self {
+        self.inner.options.json_from_str = json_from_str;
+        self
+    }
+    pub fn with_module_doc(mut self, module_doc: bool) -> Self {
+        self.inner.options.module_doc = module_doc;
+        self
+    }
+    pub fn with_eq_ignore_read_only(mut self, eq_ignore_read_only: bool) -> Self {
+        self.inner.options.eq_ignore_read_only = eq_ignore_read_only;
+        self
+    }
+    pub fn with_string_formats(mut self, string_formats: bool) -> Self {
+        self.inner.options.string_formats = string_formats;
+        self
+    }
+    pub fn with_skip_empty_collections(mut self, skip_empty_collections: bool) -> Self {
+        self.inner.options.skip_empty_collections = skip_empty_collections;
+        self
+    }
+    pub fn with_untagged_fallbacks(mut self, untagged_fallbacks: bool) -> Self {
+        self.inner.options.untagged_fallbacks = untagged_fallbacks;
+        self
+    }
+    pub fn with_merge_patch(mut self, merge_patch: bool) -> Self {
+        self.inner.options.merge_patch = merge_patch;
+        self
+    }
+    pub fn with_visitor(mut self, visitor: &'a dyn SchemaVisitor) -> Self {
+        self.inner.options.visitor = Some(visitor);
+        self
+    }
+    pub fn with_string_integers(mut self, string_integers: bool) -> Self {
+        self.inner.options.string_integers = string_integers;
+        self
+    }
+    pub fn with_typed_map_keys(mut self, typed_map_keys: bool) -> Self {
+        self.inner.options.typed_map_keys = typed_map_keys;
+        self
+    }
+    pub fn with_box_variants(mut self, box_variants: bool) -> Self {
+        self.inner.options.box_variants = box_variants;
+        self
+    }
+    pub fn with_example_fixtures(mut self, example_fixtures: bool) -> Self {
+        self.inner.options.example_fixtures = example_fixtures;
+        self
+    }
+    pub fn with_str_eq(mut self, str_eq: bool) -> Self {
+        self.inner.options.str_eq = str_eq;
+        self
+    }
+    pub fn with_unix_timestamps(mut self, unix_timestamps: bool) -> Self {
+        self.inner.options.unix_timestamps = unix_timestamps;
+        self
+    }
     pub fn build(self) -> Generator<'a, 'b> {
         self.inner
     }
 }
 
+/// Reads `input_file`, relative to the root of the crate being built unless it
+/// is absolute, returning the path it was read from and its contents.
+fn read_file(input_file: &Path) -> Result<(PathBuf, String), SchemafyError> {
+    let input_file = if input_file.is_relative() {
+        get_crate_root()?.join(input_file)
+    } else {
+        PathBuf::from(input_file)
+    };
+
+    let contents = std::fs::read_to_string(&input_file).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!("Unable to read `{}`: {}", input_file.to_string_lossy(), err),
+        )
+    })?;
+    Ok((input_file, contents))
+}
+
+/// The `$id` of a schema file, which is not a field of `Schema` since it
+/// would share its name with the `id` of draft-04. YAML is also JSON for
+/// the purpose of finding it.
+fn document_id(contents: &str) -> Option<String> {
+    #[cfg(feature = "yaml")]
+    let value = serde_yaml::from_str::<serde_json::Value>(contents).ok()?;
+    #[cfg(not(feature = "yaml"))]
+    let value = serde_json::from_str::<serde_json::Value>(contents).ok()?;
+    Some(value.get("$id")?.as_str()?.to_string())
+}
+
+fn parse_file(input_file: &Path, contents: &str) -> Result<Schema, SchemafyError> {
+    let is_yaml = matches!(
+        input_file.extension().and_then(|ext| ext.to_str()),
+        Some("yaml") | Some("yml")
+    );
+    if is_yaml {
+        #[cfg(feature = "yaml")]
+        return crate::parse_yaml(contents).map_err(|err| {
+            SchemafyError::Parse(format!(
+                "Cannot parse `{}` as YAML: {}",
+                input_file.to_string_lossy(),
+                err
+            ))
+        });
+        #[cfg(not(feature = "yaml"))]
+        return Err(SchemafyError::Unsupported(format!(
+            "Reading `{}` requires the `yaml` feature",
+            input_file.to_string_lossy()
+        )));
+    }
+    crate::parse(contents).map_err(|err| {
+        SchemafyError::Parse(format!(
+            "Cannot parse `{}` as JSON: {}",
+            input_file.to_string_lossy(),
+            err
+        ))
+    })
+}
+
+/// Returns the names of the types which were added, removed or changed
+/// between two results of [`Generator::type_hashes`].
+pub fn changed_types(
+    previous: &BTreeMap<String, u64>,
+    current: &BTreeMap<String, u64>,
+) -> Vec<String> {
+    let mut changed = current
+        .iter()
+        .filter(|(name, hash)| previous.get(*name) != Some(*hash))
+        .map(|(name, _)| name.clone())
+        .chain(
+            previous
+                .keys()
+                .filter(|name| !current.contains_key(*name))
+                .cloned(),
+        )
+        .collect::<Vec<_>>();
+    changed.sort();
+    changed
+}
+
 fn get_crate_root() -> std::io::Result<PathBuf> {
     if let Ok(path) = std::env::var("CARGO_MANIFEST_DIR") {
         return Ok(PathBuf::from(path));
@@ -115,7 +819,6 @@ fn get_crate_root() -> std::io::Result<PathBuf> {
 
     for p in current_dir.ancestors() {
         if std::fs::read_dir(p)?
-            .into_iter()
             .filter_map(Result::ok)
             .any(|p| p.file_name().eq("Cargo.toml"))
         {
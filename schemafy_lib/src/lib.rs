@@ -36,7 +36,7 @@
 //!
 //! let json = std::fs::read_to_string("src/schema.json").expect("Read schema JSON file");
 //!
-//! let schema = serde_json::from_str(&json).unwrap();
+//! let schema = schemafy_lib::parse(&json).unwrap();
 //! let mut expander = Expander::new(
 //!     Some("Schema"),
 //!     "::schemafy_core::",
@@ -52,14 +52,29 @@ extern crate serde_derive;
 #[macro_use]
 extern crate quote;
 
+mod analyze;
+mod bundle;
+mod conversion;
+mod draft;
+mod error;
 pub mod generator;
+mod report;
 
 /// Types from the JSON Schema meta-schema (draft 4).
 ///
 /// This module is itself generated from a JSON schema.
 mod schema;
+mod visitor;
 
-use std::{borrow::Cow, convert::TryFrom};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet},
+    convert::TryFrom,
+    ops::Deref,
+    rc::Rc,
+    sync::OnceLock,
+};
 
 use inflector::Inflector;
 
@@ -67,12 +82,103 @@ use serde_json::Value;
 
 use uriparse::{Fragment, URI};
 
-pub use schema::{Schema, SimpleTypes};
+pub use analyze::{analyze, Unsupported};
+pub use conversion::{expand_conversions, FieldMatching};
+pub use draft::Draft;
+pub use error::SchemafyError;
+pub use report::GenerationReport;
+pub use schema::{Components, ExclusiveBound, Schema, SimpleTypes};
+pub use visitor::SchemaVisitor;
 
-pub use generator::{Generator, GeneratorBuilder};
+pub use generator::{changed_types, Formatter, Generator, GeneratorBuilder, Options};
 
 use proc_macro2::{Span, TokenStream};
 
+/// Parses a JSON schema so that it can be inspected before it is passed to
+/// [`Generator::generate_schema`] or an [`Expander`].
+pub fn parse(json: &str) -> Result<Schema, SchemafyError> {
+    serde_json::from_str(json).or_else(|err| match serde_json::from_str(json) {
+        // The error of the value is lost, it is the same as `err`
+        Ok(value) => parse_value(&value).map_err(|_| err.into()),
+        Err(_) => Err(err.into()),
+    })
+}
+
+/// Converts an already parsed JSON value such as a schema assembled in memory,
+/// without serializing it again.
+pub fn parse_value(value: &Value) -> Result<Schema, SchemafyError> {
+    use serde::Deserialize;
+    Schema::deserialize(value).or_else(|err| {
+        let mut value = value.clone();
+        if !replace_boolean_schemas(&mut value) {
+            return Err(err.into());
+        }
+        Schema::deserialize(&value).map_err(|_| err.into())
+    })
+}
+
+/// Replaces the boolean subschemas of `schema` such as `"items": false`, which
+/// do not deserialize into a `Schema`, by the equivalent `{}` and
+/// `{"not": {}}`. The keywords which are kept as JSON values, such as
+/// `additionalProperties`, keep their booleans. Returns whether anything was
+/// replaced.
+fn replace_boolean_schemas(schema: &mut Value) -> bool {
+    fn replace(schema: &mut Value) -> bool {
+        match schema {
+            Value::Bool(true) => *schema = serde_json::json!({}),
+            Value::Bool(false) => *schema = serde_json::json!({ "not": {} }),
+            schema => return replace_boolean_schemas(schema),
+        }
+        true
+    }
+
+    let map = match schema {
+        Value::Object(map) => map,
+        _ => return false,
+    };
+    let mut replaced = false;
+    for (keyword, value) in map.iter_mut() {
+        replaced |= match (keyword.as_str(), value) {
+            ("items" | "prefixItems" | "allOf" | "anyOf" | "oneOf", Value::Array(schemas)) => {
+                schemas
+                    .iter_mut()
+                    .fold(false, |replaced, s| replace(s) | replaced)
+            }
+            ("items" | "not" | "contentSchema" | "propertyNames", schema) => replace(schema),
+            ("definitions" | "properties" | "patternProperties", Value::Object(schemas)) => schemas
+                .values_mut()
+                .fold(false, |replaced, s| replace(s) | replaced),
+            ("components", components) => replace_boolean_schemas(components),
+            ("schemas", Value::Object(schemas)) => schemas
+                .values_mut()
+                .fold(false, |replaced, s| replace(s) | replaced),
+            ("additionalProperties" | "additionalItems", schema) => replace_boolean_schemas(schema),
+            _ => false,
+        };
+    }
+    replaced
+}
+
+/// Generates the Rust types of a schema given as a JSON value, with the
+/// default options apart from `root_name`.
+pub fn generate_value(root_name: Option<&str>, value: &Value) -> Result<String, SchemafyError> {
+    let schema = parse_value(value)?;
+    let tokens = Generator::builder()
+        .with_root_name(root_name.map(str::to_string))
+        .build()
+        .try_generate_schema(&schema)?;
+    Ok(tokens.to_string())
+}
+
+/// Parses a JSON schema written in YAML.
+#[cfg(feature = "yaml")]
+pub fn parse_yaml(yaml: &str) -> Result<Schema, SchemafyError> {
+    serde_yaml::from_str(yaml).or_else(|err| match serde_yaml::from_str(yaml) {
+        Ok(value) => parse_value(&value).map_err(|_| err.into()),
+        Err(_) => Err(err.into()),
+    })
+}
+
 fn replace_invalid_identifier_chars(s: &str) -> String {
     s.strip_prefix('$')
         .unwrap_or(s)
@@ -87,6 +193,36 @@ fn replace_numeric_start(s: &str) -> String {
     }
 }
 
+/// Decodes a segment of a JSON pointer in a URI fragment, first the percent
+/// encoding of the URI and then the `~1` (`/`) and `~0` (`~`) escapes of the
+/// pointer.
+fn unescape_pointer_segment(segment: &str) -> String {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut rest = segment.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = if byte == b'%' {
+            tail.get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes)
+        .replace("~1", "/")
+        .replace("~0", "~")
+}
+
 fn remove_excess_underscores(s: &str) -> String {
     let mut result = String::new();
     let mut char_iter = s.chars().peekable();
@@ -153,25 +289,448 @@ fn rename_keyword(prefix: &str, s: &str) -> Option<TokenStream> {
     }
 }
 
-fn field(s: &str) -> TokenStream {
-    if let Some(t) = rename_keyword("pub", s) {
-        return t;
+/// Appends a number to the name of an enum variant if the enum already has a
+/// variant of that name, as values such as `fooBar` and `foo-bar` have the
+/// same PascalCase name.
+fn unique_variant(used: &mut BTreeSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut n = 2;
+    while !used.insert(str_to_ident(&unique).to_string()) {
+        unique = format!("{}{}", name, n);
+        n += 1;
+    }
+    unique
+}
+
+/// The names of the variants of an `enum`, taken from `enumNames` or
+/// `x-enum-varnames`.
+fn enum_names(schema: &Schema) -> Option<&Vec<String>> {
+    // OpenAPI generators use `x-enum-varnames` in the same way as `enumNames`
+    schema
+        .enum_names
+        .as_ref()
+        .or(schema.x_enum_varnames.as_ref())
+        .filter(|e| !e.is_empty())
+}
+
+/// The name of the variant of each value of an `enum`, `None` for the
+/// values which are not named by `enumNames` and are not strings.
+fn enum_variant_names(schema: &Schema) -> Vec<Option<String>> {
+    let values = schema.enum_.as_deref().unwrap_or_default();
+    let mut used = BTreeSet::new();
+    match enum_names(schema) {
+        Some(names) => {
+            if names.len() != values.len() {
+                panic!(
+                    "enumNames(length {}) and enum(length {}) have different length",
+                    names.len(),
+                    values.len()
+                )
+            }
+            names
+                .iter()
+                .map(|name| Some(unique_variant(&mut used, name.to_pascal_case())))
+                .collect()
+        }
+        None => values
+            .iter()
+            .map(|value| {
+                let value = value.as_str()?;
+                Some(unique_variant(&mut used, value.to_pascal_case()))
+            })
+            .collect(),
+    }
+}
+
+/// Whether every string of the `enum` is the name of its variant in
+/// SCREAMING_SNAKE_CASE, so that `rename_all` can replace their renames.
+fn is_screaming_snake_enum(schema: &Schema) -> bool {
+    let values = schema.enum_.as_deref().unwrap_or_default();
+    enum_names(schema).is_none()
+        && values.iter().any(Value::is_string)
+        && enum_variant_names(schema)
+            .iter()
+            .zip(values)
+            .all(|(name, value)| match (name, value) {
+                (Some(name), Value::String(value)) => {
+                    str_to_ident(name) == name && screaming_snake_case(name) == *value
+                }
+                (_, value) => value.is_null(),
+            })
+}
+
+/// The name `#[serde(rename_all = "SCREAMING_SNAKE_CASE")]` gives the variant
+/// `name`.
+fn screaming_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.char_indices() {
+        if i > 0 && c.is_uppercase() {
+            result.push('_');
+        }
+        result.push(c.to_ascii_uppercase());
+    }
+    result
+}
+
+fn variant_ident(name: &str) -> TokenStream {
+    rename_keyword("", name).unwrap_or_else(|| {
+        let ident = syn::Ident::new(name, Span::call_site());
+        quote!(#ident)
+    })
+}
+
+/// How the names of the schema become identifiers, apart from the characters
+/// which are not allowed in identifiers. A `rename` to the name in the schema
+/// is emitted for every identifier which differs from it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IdentifierCase {
+    /// snake_case fields and PascalCase types.
+    Rust,
+    /// camelCase fields and PascalCase types.
+    Camel,
+    /// The names as they are.
+    Preserve,
+}
+
+impl IdentifierCase {
+    /// The name of the type generated for the definition `name`.
+    fn type_name(self, name: &str) -> String {
+        let name = match self {
+            IdentifierCase::Rust | IdentifierCase::Camel => name.to_pascal_case(),
+            IdentifierCase::Preserve => name.to_string(),
+        };
+        replace_invalid_identifier_chars(&name)
     }
-    let snake = s.to_snake_case();
-    if snake == s && !snake.contains(|c: char| c == '$' || c == '#') {
-        let field = syn::Ident::new(s, Span::call_site());
-        return quote!( pub #field );
+}
+
+/// How a `oneOf` is represented.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OneOfTagging {
+    /// Untagged enums.
+    Untagged,
+    /// Adjacently tagged enums (`#[serde(tag, content)]`) when every variant is
+    /// an object of a tag property with a single string value and a content
+    /// property, untagged enums otherwise.
+    Auto,
+    /// Like `Auto` but the `oneOf`s which are not adjacently tagged are an
+    /// error, see [`Expander::check_one_of_tagging`].
+    Adjacent,
+}
+
+fn field(prefix: &str, s: &str, case: IdentifierCase) -> TokenStream {
+    let prefix = if prefix.is_empty() {
+        None
+    } else {
+        Some(syn::Ident::new(prefix, Span::call_site()))
+    };
+    let field = field_ident(s, case);
+    if field == s {
+        quote!( #prefix #field )
+    } else {
+        quote! {
+            #[serde(rename = #s)]
+            #prefix #field
+        }
     }
+}
 
-    let field = if snake.is_empty() {
+/// The name of the field generated for the property `s`.
+fn field_ident(s: &str, case: IdentifierCase) -> syn::Ident {
+    // Keys such as `""` and `" "` have no characters to name the field after,
+    // they are told apart by their length instead
+    if s.trim().is_empty() {
+        return match s.chars().count() {
+            0 => format_ident!("field_"),
+            n => format_ident!("field_{}", n),
+        };
+    }
+    let ident = str_to_ident(s);
+    let name = match case {
+        // Names which are lowercase once they are valid identifiers are kept
+        // while names such as `$recursiveRef` are converted to snake case
+        IdentifierCase::Rust
+            if ident != s && !ident.to_string().chars().any(char::is_uppercase) =>
+        {
+            return ident;
+        }
+        IdentifierCase::Rust => s.to_snake_case(),
+        IdentifierCase::Camel => s.to_camel_case(),
+        IdentifierCase::Preserve => return ident,
+    };
+    if name == s {
+        ident
+    } else if name.is_empty() {
         syn::Ident::new("underscore", Span::call_site())
     } else {
-        str_to_ident(&snake)
+        str_to_ident(&name)
+    }
+}
+
+/// The property dependencies (`dependencies` and `dependentRequired`) of a
+/// schema, `None` marks a dependency on a schema instead of properties.
+fn dependencies(schema: &Schema) -> Vec<(String, Option<Vec<String>>)> {
+    let mut result = Vec::new();
+    for (name, dependency) in schema.dependencies.iter().flatten() {
+        let required = dependency.as_array().map(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        });
+        result.push((name.clone(), required));
+    }
+    for (name, required) in schema.dependent_required.iter().flatten() {
+        result.push((name.clone(), Some(required.clone())));
+    }
+    result
+}
+
+/// Documents the `dependencies` of a struct since they can't be expressed
+/// in its type.
+fn dependency_docs(dependencies: &[(String, Option<Vec<String>>)]) -> Vec<TokenStream> {
+    dependencies
+        .iter()
+        .map(|(name, required)| {
+            let doc = match required {
+                Some(required) => format!(
+                    " If `{}` is present then `{}` must be present as well.",
+                    name,
+                    required.join("`, `")
+                ),
+                None => format!(
+                    " If `{}` is present then the object must also match a dependent schema.",
+                    name
+                ),
+            };
+            quote!(#[doc = #doc])
+        })
+        .collect()
+}
+
+/// The `definitions` of `schema` followed by the `components.schemas` of an
+/// OpenAPI document, which are generated the same way.
+fn definitions(schema: &Schema) -> impl Iterator<Item = (&String, &Schema)> {
+    schema
+        .definitions
+        .iter()
+        .chain(schema.components.iter().flat_map(|c| &c.schemas))
+}
+
+/// Whether the property `name` of `schema` with the schema `property` is
+/// required, which `x-rust-optional` overrides.
+fn is_required(schema: &Schema, name: &str, property: &Schema) -> bool {
+    match property.x_rust_optional {
+        Some(optional) => !optional,
+        None => schema.required.iter().flatten().any(|req| req == name),
+    }
+}
+
+/// Whether `schema` is nothing but a `$ref`, apart from its documentation and
+/// definitions, and so stands for the same type as what it refers to.
+fn is_ref_alias(schema: &Schema) -> bool {
+    let ref_ = match &schema.ref_ {
+        Some(ref_) => ref_,
+        None => return false,
+    };
+    let mut alias: Schema = serde_json::from_value(serde_json::json!({ "$ref": ref_ })).unwrap();
+    alias.title.clone_from(&schema.title);
+    alias.description.clone_from(&schema.description);
+    alias.comment.clone_from(&schema.comment);
+    // The definitions are generated on their own, e.g. those of a root which
+    // only refers to one of them
+    alias.definitions.clone_from(&schema.definitions);
+    alias.schema.clone_from(&schema.schema);
+    alias.id.clone_from(&schema.id);
+    alias == *schema
+}
+
+/// The `prefixItems` of a tuple of a fixed length, which allows no other
+/// items through `"items": false`.
+fn tuple_items(schema: &Schema) -> Option<&[Schema]> {
+    let no_items: Schema = serde_json::from_value(serde_json::json!({ "not": {} })).unwrap();
+    match (schema.prefix_items.as_deref(), &schema.items[..]) {
+        (Some(prefix_items), [items]) if !prefix_items.is_empty() && *items == no_items => {
+            Some(prefix_items)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the `$ref` `s` points into a definition, at one of its
+/// `properties`, `items` or `additionalProperties`, instead of at a definition.
+fn is_nested_ref(s: &str) -> bool {
+    let pointer = s.split_once('#').map_or("", |(_, pointer)| pointer);
+    let mut comps = pointer.split('/');
+    while let Some(comp) = comps.next() {
+        match comp {
+            // Skips the name which follows
+            "definitions" | "schemas" => {
+                comps.next();
+            }
+            "properties" | "items" | "additionalProperties" => return true,
+            _ => (),
+        }
+    }
+    false
+}
+
+/// The document of the `$ref` `s`, empty if it points into the same document.
+fn document(s: &str) -> &str {
+    s.split('#').next().unwrap_or_default()
+}
+
+/// The `$ref` of a schema. `$recursiveRef` and `$dynamicRef` are treated as
+/// references to the document root unless they are a JSON pointer, full
+/// dynamic scope resolution is not supported.
+fn reference(schema: &Schema) -> Option<&str> {
+    schema.ref_.as_deref().or_else(|| {
+        let ref_ = schema
+            .recursive_ref
+            .as_deref()
+            .or(schema.dynamic_ref.as_deref())?;
+        Some(if ref_.contains("#/") { ref_ } else { "#" })
+    })
+}
+
+/// The conditions under which the property `dependencies` of a struct are
+/// violated, along with the error message.
+fn dependency_checks<'d>(
+    dependencies: &'d [(String, Option<Vec<String>>)],
+    optional: &'d [String],
+    case: IdentifierCase,
+) -> impl Iterator<Item = (TokenStream, String)> + 'd {
+    // Fields which are not optional are always present so only missing
+    // optional fields can violate a dependency
+    dependencies
+        .iter()
+        .filter_map(|(name, required)| Some((name, required.as_ref()?)))
+        .flat_map(|(name, required)| required.iter().map(move |r| (name, r)))
+        .filter(move |(_, required)| optional.contains(required))
+        .map(move |(name, required)| {
+            let message = format!("`{}` is required when `{}` is present", required, name);
+            let required = field_ident(required, case);
+            let present = if optional.contains(name) {
+                let name = field_ident(name, case);
+                Some(quote!(self.#name.is_some() &&))
+            } else {
+                None
+            };
+            (quote!(#present self.#required.is_none()), message)
+        })
+}
+
+/// The schema of the JSON inside a string with `contentMediaType:
+/// application/json`.
+fn json_content(schema: &Schema) -> Option<&Schema> {
+    if schema.content_media_type.as_deref() == Some("application/json") {
+        schema.content_schema.as_deref()
+    } else {
+        None
+    }
+}
+
+/// `path` as it is written in the types of the fields, without spaces.
+fn path_string(path: &syn::Path) -> String {
+    quote!(#path).to_string().replace(' ', "")
+}
+
+/// Whether the derive `derive` is of the trait `name`, possibly given by its
+/// path.
+fn is_derive(derive: &TokenStream, name: &str) -> bool {
+    derive.to_string().rsplit("::").next().map(str::trim) == Some(name)
+}
+
+/// The derives of an enum of unit variants, which can always be hashed and
+/// compared for equality so they also derive `Hash` and `Eq` (given
+/// `PartialEq`), e.g. to be used as the keys of a `HashMap`. `ordered` adds
+/// `PartialOrd` and `Ord` for the keys of a `BTreeMap`.
+fn unit_enum_derives(mut derives: Vec<TokenStream>, ordered: bool) -> Vec<TokenStream> {
+    let has = |derives: &[TokenStream], name| derives.iter().any(|d| is_derive(d, name));
+    if has(&derives, "PartialEq") && !has(&derives, "Eq") {
+        derives.push(quote!(Eq));
+    }
+    if !has(&derives, "Hash") {
+        derives.push(quote!(Hash));
+    }
+    if ordered && !has(&derives, "PartialOrd") {
+        derives.push(quote!(PartialOrd));
+    }
+    if ordered && !has(&derives, "Ord") {
+        derives.push(quote!(Ord));
+    }
+    derives
+}
+
+/// Whether the schema describes a sensitive value which `redact_secrets` hides.
+fn is_secret(schema: &Schema) -> bool {
+    schema.format.as_deref() == Some("password") || schema.write_only == Some(true)
+}
+
+/// A bound of a number and whether it is exclusive. `exclusive` makes the
+/// `bound` exclusive if it is a boolean (draft-04) while a number is an
+/// exclusive bound of its own (draft-06 and later), of which the tighter
+/// bound is used.
+fn number_bound(
+    bound: Option<f64>,
+    exclusive: &Option<ExclusiveBound>,
+    tighter: fn(f64, f64) -> bool,
+) -> (Option<f64>, bool) {
+    match (bound, exclusive) {
+        (_, Some(ExclusiveBound::Bool(exclusive))) => (bound, *exclusive && bound.is_some()),
+        (Some(bound), Some(ExclusiveBound::Number(exclusive))) if !tighter(*exclusive, bound) => {
+            (Some(bound), false)
+        }
+        (_, Some(ExclusiveBound::Number(exclusive))) => (Some(*exclusive), true),
+        (_, None) => (bound, false),
+    }
+}
+
+/// The type of an integer schema, which is 128 bits wide if the `format` says
+/// so or if the bounds of the schema do not fit in an `i64`.
+fn integer_type(schema: &Schema) -> &'static str {
+    match schema.format.as_deref() {
+        Some("int128") => return "i128",
+        Some("uint128") => return "u128",
+        _ => (),
+    }
+    // The bounds are `f64`s so `i64::MAX` is rounded up to 2^63, which has to
+    // be accepted for a schema declaring that maximum
+    let fits = |bound: Option<f64>| {
+        bound.is_none_or(|bound| bound >= i64::MIN as f64 && bound <= i64::MAX as f64)
     };
+    if fits(schema.minimum) && fits(schema.maximum) {
+        "i64"
+    } else if schema.minimum.is_some_and(|minimum| minimum >= 0.0) {
+        "u128"
+    } else {
+        "i128"
+    }
+}
+
+/// Whether `schema` is an integer counting the seconds since the Unix epoch.
+fn is_unix_time(schema: &Schema) -> bool {
+    schema.type_.contains(&SimpleTypes::Integer) && schema.format.as_deref() == Some("unix-time")
+}
+
+/// Whether the generated type `typ` implements `Ord` and so can be the item of a
+/// `BTreeSet`.
+fn is_ordered(typ: &str) -> bool {
+    matches!(typ, "String" | "&'a str" | "i64" | "i128" | "u128" | "bool")
+        || typ.ends_with("OrderedFloat<f64>")
+}
+
+/// Strips the lifetime which `borrow` adds to generated types.
+fn without_lifetime(typ: &str) -> &str {
+    typ.strip_suffix("<'a>").unwrap_or(typ)
+}
 
-    quote! {
-        #[serde(rename = #s)]
-        pub #field
+/// `#[serde(borrow)]` for fields which borrow from the input.
+fn serde_borrow(typ: &TokenStream) -> Option<TokenStream> {
+    if typ.to_string().contains("'a") {
+        Some(quote!(#[serde(borrow)]))
+    } else {
+        None
     }
 }
 
@@ -181,21 +740,92 @@ where
     T: Clone,
 {
     *result = match (&mut result, r) {
-        (&mut &mut Some(ref mut result), &Some(ref r)) => return f(result, r),
-        (&mut &mut None, &Some(ref r)) => Some(r.clone()),
+        (&mut &mut Some(ref mut result), Some(r)) => return f(result, r),
+        (&mut &mut None, Some(r)) => Some(r.clone()),
         _ => return,
     };
 }
 
-fn merge_all_of(result: &mut Schema, r: &Schema) {
-    use std::collections::btree_map::Entry;
+/// The `anyOf` or `oneOf` of `schema` without its `{"type": "null"}` branch, for
+/// the idiom of a nullable value. The other branch is returned on its own if
+/// there is only one. `None` unless exactly one of the branches is `null`.
+fn without_null_branch(schema: &Schema) -> Option<Schema> {
+    let is_null = |branch: &Schema| branch.type_ == [SimpleTypes::Null];
+    let branches = schema.any_of.as_ref().or(schema.one_of.as_ref())?;
+    if branches.len() < 2 || branches.iter().filter(|branch| is_null(branch)).count() != 1 {
+        return None;
+    }
+    let mut rest = branches
+        .iter()
+        .filter(|branch| !is_null(branch))
+        .cloned()
+        .collect::<Vec<_>>();
+    if rest.len() == 1 {
+        return rest.pop();
+    }
+    let mut result = schema.clone();
+    if result.any_of.is_some() {
+        result.any_of = Some(rest);
+    } else {
+        result.one_of = Some(rest);
+    }
+    Some(result)
+}
+
+/// Rewrites an `anyOf` or `oneOf` whose branches are all `const` strings as
+/// the equivalent `enum`, keeping the descriptions of the branches for the
+/// variants. The variants are named after the `title`s of the branches, those
+/// without one after their value.
+fn const_enum(schema: &Schema) -> Option<Schema> {
+    let branches = schema
+        .any_of
+        .as_ref()
+        .or(schema.one_of.as_ref())
+        .filter(|branches| !branches.is_empty())?;
+    let values = branches
+        .iter()
+        .map(|branch| branch.const_.clone().filter(Value::is_string))
+        .collect::<Option<Vec<_>>>()?;
+    let mut result = schema.clone();
+    result.any_of = None;
+    result.one_of = None;
+    result.type_ = vec![SimpleTypes::String];
+    result.enum_ = Some(values);
+    if branches.iter().any(|branch| branch.description.is_some()) {
+        result.x_enum_descriptions = branches
+            .iter()
+            .map(|branch| branch.description.clone().unwrap_or_default())
+            .collect();
+    }
+    if branches.iter().any(|branch| branch.title.is_some()) && enum_names(&result).is_none() {
+        result.enum_names = Some(
+            branches
+                .iter()
+                .zip(result.enum_.iter().flatten())
+                .map(|(branch, value)| {
+                    branch
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| value.as_str().unwrap().to_string())
+                })
+                .collect(),
+        );
+    }
+    Some(result)
+}
+
+/// Whether `schema` is an object with known properties.
+fn is_object(schema: &Schema) -> bool {
+    schema.type_ == [SimpleTypes::Object] && !schema.properties.is_empty()
+}
 
+fn merge_all_of(result: &mut Schema, r: &Schema) {
     for (k, v) in &r.properties {
-        match result.properties.entry(k.clone()) {
-            Entry::Vacant(entry) => {
-                entry.insert(v.clone());
+        match result.properties.get_mut(k) {
+            Some(property) => merge_all_of(property, v),
+            None => {
+                result.properties.insert(k.clone(), v.clone());
             }
-            Entry::Occupied(mut entry) => merge_all_of(entry.get_mut(), v),
         }
     }
 
@@ -211,24 +841,84 @@ fn merge_all_of(result: &mut Schema, r: &Schema) {
         required.extend(r_required.iter().cloned());
     });
 
-    result.type_.retain(|e| r.type_.contains(e));
+    merge_option(
+        &mut result.dependencies,
+        &r.dependencies,
+        |dependencies, r_dependencies| {
+            dependencies.extend(r_dependencies.clone());
+        },
+    );
+
+    merge_option(
+        &mut result.dependent_required,
+        &r.dependent_required,
+        |dependent_required, r_dependent_required| {
+            dependent_required.extend(r_dependent_required.clone());
+        },
+    );
+
+    // A part without `type` does not restrict the type
+    if !r.type_.is_empty() {
+        result.type_ = common_types(&result.type_, &r.type_);
+    }
+}
+
+/// The types allowed by both `a` and `b`, where an `integer` is also a
+/// `number`.
+fn common_types(a: &[SimpleTypes], b: &[SimpleTypes]) -> Vec<SimpleTypes> {
+    let mut common = Vec::new();
+    for type_ in a {
+        let type_ = match type_ {
+            _ if b.contains(type_) => type_,
+            SimpleTypes::Number if b.contains(&SimpleTypes::Integer) => &SimpleTypes::Integer,
+            _ => continue,
+        };
+        if !common.contains(type_) {
+            common.push(type_.clone());
+        }
+    }
+    common
+}
+
+/// How many levels of references are inlined into the schemas which are
+/// interpreted at runtime.
+const INLINE_REF_DEPTH: usize = 8;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a, unlike `DefaultHasher` it is guaranteed to be stable between
+/// releases of Rust.
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100_0000_01b3);
+    }
+    hash
 }
 
 const LINE_LENGTH: usize = 100;
 const INDENT_LENGTH: usize = 4;
 
-fn make_doc_comment(mut comment: &str, remaining_line: usize) -> TokenStream {
+fn make_doc_comment(comment: &str, remaining_line: usize) -> TokenStream {
+    wrap_doc_comment("/// ", comment, remaining_line)
+        .parse()
+        .unwrap()
+}
+
+/// Wraps `comment` into lines starting with `prefix`, `/// ` or `//! `.
+fn wrap_doc_comment(prefix: &str, mut comment: &str, remaining_line: usize) -> String {
     let mut out_comment = String::new();
-    out_comment.push_str("/// ");
-    let mut length = 4;
+    out_comment.push_str(prefix);
+    let mut length = prefix.len();
     while let Some(word) = comment.split(char::is_whitespace).next() {
         if comment.is_empty() {
             break;
         }
         comment = &comment[word.len()..];
         if length + word.len() >= remaining_line {
-            out_comment.push_str("\n/// ");
-            length = 4;
+            out_comment.push('\n');
+            out_comment.push_str(prefix);
+            length = prefix.len();
         }
         out_comment.push_str(word);
         length += word.len();
@@ -236,8 +926,8 @@ fn make_doc_comment(mut comment: &str, remaining_line: usize) -> TokenStream {
         match n.next() {
             Some('\n') => {
                 out_comment.push('\n');
-                out_comment.push_str("/// ");
-                length = 4;
+                out_comment.push_str(prefix);
+                length = prefix.len();
             }
             Some(_) => {
                 out_comment.push(' ');
@@ -251,33 +941,175 @@ fn make_doc_comment(mut comment: &str, remaining_line: usize) -> TokenStream {
         out_comment.pop();
     }
     out_comment.push('\n');
-    out_comment.parse().unwrap()
+    out_comment
+}
+
+/// The `title` and `description` of `schema` as `//!` comments.
+pub(crate) fn module_doc(schema: &Schema) -> Option<String> {
+    let paragraphs = [&schema.title, &schema.description]
+        .iter()
+        .filter_map(|text| text.as_deref())
+        .map(|text| wrap_doc_comment("//! ", text, LINE_LENGTH))
+        .collect::<Vec<_>>();
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("//!\n"))
+    }
 }
 
 struct FieldExpander<'a, 'r: 'a> {
     default: bool,
+    /// Visibility of the generated fields, empty for enum variant fields
+    visibility: &'static str,
+    /// The properties which became `Option` fields
+    optional: Vec<String>,
+    /// The checks of the fields for `validate`
+    validations: Vec<TokenStream>,
+    /// The functions giving the `default` of enum fields
+    default_fns: Vec<TokenStream>,
     expander: &'a mut Expander<'r>,
 }
 
 impl<'a, 'r> FieldExpander<'a, 'r> {
+    /// The type of the property `value` with a scalar `default`, along with
+    /// the expression of the default. `None` if it has no `default` or its
+    /// type is not a string, number or boolean.
+    fn literal_default(
+        &mut self,
+        type_name: &str,
+        value: &Schema,
+    ) -> Option<(FieldType, TokenStream)> {
+        let default = self.expander.schema(value).default.clone()?;
+        let (types, report) = (self.expander.types.len(), self.expander.report.get());
+        let field_type = self.expander.expand_type(type_name, true, value);
+        let literal = match (field_type.typ.as_str(), &default) {
+            ("String", Value::String(s)) => Some(quote!(#s.to_string())),
+            ("&'a str", Value::String(s)) => Some(quote!(#s)),
+            ("bool", Value::Bool(b)) => Some(quote!(#b)),
+            ("f64", Value::Number(n)) => n.as_f64().map(|n| {
+                let n = proc_macro2::Literal::f64_unsuffixed(n);
+                quote!(#n)
+            }),
+            ("i64" | "i128", Value::Number(n)) => n.as_i64().map(|n| {
+                let n = proc_macro2::Literal::i64_unsuffixed(n);
+                quote!(#n)
+            }),
+            ("u128", Value::Number(n)) => n.as_u64().map(|n| {
+                let n = proc_macro2::Literal::u64_unsuffixed(n);
+                quote!(#n)
+            }),
+            _ => None,
+        };
+        match literal {
+            Some(literal) => Some((field_type, literal)),
+            None => {
+                // The field is expanded again as an `Option`
+                self.expander.types.truncate(types);
+                self.expander.report.set(report);
+                None
+            }
+        }
+    }
+
     fn expand_fields(&mut self, type_name: &str, schema: &Schema) -> Vec<TokenStream> {
         let schema = self.expander.schema(schema);
-        schema
-            .properties
-            .iter()
+        let owner = self.expander.type_name(&self.expander.current_type);
+        self.expander
+            .ordered(&schema.properties)
+            .into_iter()
             .map(|(field_name, value)| {
                 self.expander.current_field.clone_from(field_name);
-                let key = field(field_name);
-                let required = schema
-                    .required
-                    .iter()
-                    .flat_map(|a| a.iter())
-                    .any(|req| req == field_name);
-                let field_type = self.expander.expand_type(type_name, required, value);
-                if !field_type.typ.starts_with("Option<") {
+                let required = is_required(&schema, field_name, value);
+                // Only struct fields can refer to a function of their type
+                let enum_default = if !required && !self.visibility.is_empty() {
+                    self.expander.enum_default(value)
+                } else {
+                    None
+                };
+                // `x-extends` embeds the fields of the referenced base type
+                let extends = reference(value).filter(|_| value.x_extends == Some(true));
+                let (key, mut field_type) = match extends {
+                    Some(ref_) => {
+                        let prefix = if self.visibility.is_empty() {
+                            None
+                        } else {
+                            Some(syn::Ident::new(self.visibility, Span::call_site()))
+                        };
+                        let field = field_ident(field_name, self.expander.options.identifier_case);
+                        let field_type = FieldType {
+                            typ: self.expander.type_use(self.expander.type_ref(ref_)),
+                            attributes: vec!["flatten".into()],
+                            default: false,
+                        };
+                        (quote!(#prefix #field), field_type)
+                    }
+                    None => {
+                        let key = field(
+                            self.visibility,
+                            field_name,
+                            self.expander.options.identifier_case,
+                        );
+                        let defaulted = match enum_default {
+                            Some(variant) => {
+                                let field_type = self.expander.expand_type(type_name, true, value);
+                                let typ = field_type.typ.parse::<TokenStream>().unwrap();
+                                Some((field_type, quote!(#typ::#variant)))
+                            }
+                            None if !required && !self.visibility.is_empty() => {
+                                self.literal_default(type_name, value)
+                            }
+                            None => None,
+                        };
+                        match defaulted {
+                            // The field holds the value itself instead of an `Option`
+                            Some((mut field_type, default)) => {
+                                let function = format_ident!(
+                                    "default_{}",
+                                    field_ident(field_name, self.expander.options.identifier_case)
+                                );
+                                let typ = field_type.typ.parse::<TokenStream>().unwrap();
+                                self.default_fns.push(quote! {
+                                    fn #function() -> #typ {
+                                        #default
+                                    }
+                                });
+                                field_type
+                                    .attributes
+                                    .push(format!(r#"default = "{}::{}""#, owner, function));
+                                (key, field_type)
+                            }
+                            None => (key, self.expander.expand_type(type_name, required, value)),
+                        }
+                    }
+                };
+                let holds_value = field_type.typ.contains(&self.expander.serde_json("Value"));
+                self.expander.count(|report| {
+                    report.fields += 1;
+                    report.value_fields += usize::from(holds_value);
+                });
+                if self.expander.options.skip_empty_collections && field_type.default {
+                    if let Some(is_empty) = self.expander.is_empty_fn(&field_type.typ) {
+                        field_type
+                            .attributes
+                            .push(format!("skip_serializing_if=\"{}\"", is_empty));
+                    }
+                }
+                if field_type.typ.starts_with("Option<") {
+                    self.optional.push(field_name.clone());
+                }
+                if self.expander.options.validation {
+                    self.validations.extend(self.expander.field_validation(
+                        field_name,
+                        &field_type.typ,
+                        value,
+                    ));
+                }
+                if !self.expander.implements_default(&field_type) {
                     self.default = false;
                 }
                 let typ = field_type.typ.parse::<TokenStream>().unwrap();
+                let borrow = serde_borrow(&typ);
 
                 let default = if field_type.default {
                     Some(quote! { #[serde(default)] })
@@ -295,14 +1127,50 @@ impl<'a, 'r> FieldExpander<'a, 'r> {
                         #[serde( #(#attributes),* )]
                     })
                 };
+                self.expander
+                    .check_examples(&format!("{}.{}", type_name, field_name), value);
                 let comment = value
                     .description
                     .as_ref()
                     .map(|comment| make_doc_comment(comment, LINE_LENGTH - INDENT_LENGTH));
+                let see_also = if self.expander.options.doc_links {
+                    self.expander.doc_link(value).map(|link| {
+                        // The link is not wrapped as that could split it
+                        let separator = comment.as_ref().map(|_| quote!(#[doc = ""]));
+                        let link = format!(" See also [`{}`].", link);
+                        quote! {
+                            #separator
+                            #[doc = #link]
+                        }
+                    })
+                } else {
+                    None
+                };
+                let unique = self.expander.options.unique_item_sets
+                    && self.expander.schema(value).unique_items
+                    && field_type
+                        .typ
+                        .trim_start_matches("Option<")
+                        .starts_with("Vec<");
+                let unique = if unique {
+                    Some(quote! {
+                        #[doc = " The items are unique, the type of the items can not be put in a set."]
+                    })
+                } else {
+                    None
+                };
+                let mut visited = Vec::new();
+                if let Some(visitor) = self.expander.options.visitor {
+                    visitor.on_field(&owner, field_name, value, &mut visited);
+                }
                 quote! {
                     #comment
+                    #see_also
+                    #unique
                     #default
+                    #borrow
                     #attributes
+                    #(#visited)*
                     #key : #typ
                 }
             })
@@ -310,6 +1178,65 @@ impl<'a, 'r> FieldExpander<'a, 'r> {
     }
 }
 
+/// A schema with its `$ref` followed and its `allOf` merged, see
+/// [`Expander::schema`].
+#[derive(Clone, Debug)]
+enum Resolved<'r> {
+    Borrowed(&'r Schema),
+    Owned(Rc<Schema>),
+}
+
+impl Deref for Resolved<'_> {
+    type Target = Schema;
+
+    fn deref(&self) -> &Schema {
+        match self {
+            Resolved::Borrowed(schema) => schema,
+            Resolved::Owned(schema) => schema,
+        }
+    }
+}
+
+impl PartialEq for Resolved<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+/// The options of an [`Expander`], borrowed from its [`Generator`] or the
+/// defaults of [`Expander::new`].
+enum ExpanderOptions<'r> {
+    Borrowed(&'r Options<'r>),
+    Owned(Box<Options<'r>>),
+}
+
+impl<'r> Deref for ExpanderOptions<'r> {
+    type Target = Options<'r>;
+
+    fn deref(&self) -> &Options<'r> {
+        match self {
+            ExpanderOptions::Borrowed(options) => options,
+            ExpanderOptions::Owned(options) => options,
+        }
+    }
+}
+
+/// How far [`Expander::walk`] followed a `$ref`.
+enum Step<'a> {
+    /// The subschema which the `$ref` points to.
+    Schema(&'a Schema),
+    /// The `additionalProperties` which the rest of the `$ref` points into.
+    AdditionalProperties(&'a Value),
+}
+
+/// The `oneOf` of an adjacently tagged enum, see
+/// [`Expander::adjacent_envelope`].
+struct AdjacentEnvelope {
+    tag: String,
+    content: String,
+    variants: Vec<(String, Schema)>,
+}
+
 pub struct Expander<'r> {
     root_name: Option<&'r str>,
     schemafy_path: &'r str,
@@ -317,6 +1244,33 @@ pub struct Expander<'r> {
     current_type: String,
     current_field: String,
     types: Vec<(String, TokenStream)>,
+    /// The already generated types which implement `Default`
+    defaults: BTreeSet<String>,
+    /// The generated types which have a lifetime as they borrow from the input
+    borrowed: BTreeSet<String>,
+    /// The generated types which have a `validate` method
+    validated: BTreeSet<String>,
+    /// The `$comment`s of the schema, keyed by the JSON pointer of their subschema
+    comments: Vec<(String, String)>,
+    /// The merged `allOf`s of the subschemas of `root`, keyed by their address. The
+    /// entries are `None` until the subschema is first merged.
+    ///
+    /// The addresses stay valid because `root` is borrowed for as long as the
+    /// expander lives, so its subschemas can neither move nor be freed. Only
+    /// the keys collected from `root` up front are ever filled in, a schema
+    /// which is not part of `root` (such as a merged temporary, whose address
+    /// may be reused) is merged again each time.
+    merged: RefCell<BTreeMap<*const Schema, Option<Rc<Schema>>>>,
+    /// The subschemas inside `additionalProperties` which `$ref`s point to, keyed by
+    /// their `$ref`
+    additional_properties: RefCell<BTreeMap<String, Rc<Schema>>>,
+    /// The counts of the types and fields generated by the current pass of `expand`
+    report: Cell<GenerationReport>,
+    /// The `examples` found by the current pass of `expand` which do not fit the
+    /// types generated for them
+    example_mismatches: RefCell<Vec<String>>,
+    /// The options of the generated code
+    options: ExpanderOptions<'r>,
 }
 
 struct FieldType {
@@ -343,18 +1297,59 @@ impl<'r> Expander<'r> {
         root_name: Option<&'r str>,
         schemafy_path: &'r str,
         root: &'r Schema,
+    ) -> Expander<'r> {
+        Expander::with(
+            root_name,
+            schemafy_path,
+            root,
+            ExpanderOptions::Owned(Box::default()),
+        )
+    }
+
+    /// Like [`new`](Self::new) but generates the code with `options` instead
+    /// of the default ones.
+    pub fn with_options(
+        root_name: Option<&'r str>,
+        schemafy_path: &'r str,
+        root: &'r Schema,
+        options: &'r Options<'r>,
+    ) -> Expander<'r> {
+        Expander::with(
+            root_name,
+            schemafy_path,
+            root,
+            ExpanderOptions::Borrowed(options),
+        )
+    }
+
+    fn with(
+        root_name: Option<&'r str>,
+        schemafy_path: &'r str,
+        root: &'r Schema,
+        options: ExpanderOptions<'r>,
     ) -> Expander<'r> {
         Expander {
             root_name,
             root,
             schemafy_path,
+            options,
             current_field: "".into(),
             current_type: "".into(),
             types: Vec::new(),
+            defaults: BTreeSet::new(),
+            borrowed: BTreeSet::new(),
+            validated: BTreeSet::new(),
+            comments: Vec::new(),
+            merged: RefCell::new(all_ofs(root)),
+            additional_properties: RefCell::new(BTreeMap::new()),
+            report: Cell::new(GenerationReport::default()),
+            example_mismatches: RefCell::new(Vec::new()),
         }
     }
 
     fn type_ref(&self, s: &str) -> String {
+        let resolved = self.resolve_ref(s);
+        let s = &*resolved;
         // ref is supposed to be be a valid URI, however we should better have a fallback plan
         let fragment = URI::try_from(s)
             .map(|uri| uri.fragment().map(Fragment::to_owned))
@@ -367,63 +1362,558 @@ impl<'r> Expander<'r> {
             .map(|fragment| fragment.to_string())
             .unwrap_or_else(|| s.to_owned());
 
-        let ref_ = if fragment.is_empty() {
-            self.root_name.expect("No root name specified for schema")
-        } else {
-            fragment.split('/').last().expect("Component")
-        };
+        let module = self.ref_module(s);
+        let ref_ = match module {
+            Some(_) => match s.split_once('#').map(|(_, pointer)| pointer) {
+                Some(pointer) if !pointer.is_empty() => {
+                    pointer.split('/').next_back().expect("Component")
+                }
+                // The root of another document is named after its file
+                _ => {
+                    let file = document(s).rsplit('/').next().unwrap_or_default();
+                    file.split('.').next().unwrap_or_default()
+                }
+            },
+            None if fragment.is_empty() => {
+                self.root_name.expect("No root name specified for schema")
+            }
+            None => fragment.split('/').next_back().expect("Component"),
+        };
 
-        let ref_ = ref_.to_pascal_case();
-        let ref_ = replace_invalid_identifier_chars(&ref_);
-        replace_numeric_start(&ref_)
+        let ref_ = self
+            .options
+            .identifier_case
+            .type_name(&unescape_pointer_segment(ref_));
+        let name = self.type_name(&replace_numeric_start(&ref_));
+        match module {
+            Some(module) => format!("{}::{}", path_string(module), name),
+            None => name,
+        }
     }
 
-    fn schema(&self, schema: &'r Schema) -> Cow<'r, Schema> {
-        let schema = match schema.ref_ {
-            Some(ref ref_) => self.schema_ref(ref_),
-            None => schema,
+    /// Follows the `$ref` `s` through the definitions which are only a `$ref`
+    /// themselves, to the definition of the type which they all stand for.
+    fn resolve_ref<'s>(&'s self, s: &'s str) -> Cow<'s, str> {
+        let mut s = Cow::Borrowed(s);
+        // Cyclic chains have no end so they are only followed for a while
+        for _ in 0..16 {
+            if self.ref_module(&s).is_some() {
+                break;
+            }
+            // A `$ref` into a definition has no type of its own to name
+            let is_alias = |target: &Schema| {
+                is_ref_alias(target) && !is_nested_ref(target.ref_.as_deref().unwrap())
+            };
+            s = match self.try_schema_ref(&s) {
+                Ok(Resolved::Borrowed(target)) if is_alias(target) => {
+                    Cow::Borrowed(target.ref_.as_deref().unwrap())
+                }
+                Ok(Resolved::Owned(target)) if is_alias(&target) => {
+                    Cow::Owned(target.ref_.clone().unwrap())
+                }
+                _ => break,
+            };
+        }
+        s
+    }
+
+    /// The module configured in `ref_modules` for the document which the
+    /// `$ref` `s` points into, if it points into another document.
+    fn ref_module(&self, s: &str) -> Option<&syn::Path> {
+        let document = document(s);
+        if document.is_empty() {
+            return None;
+        }
+        self.options.ref_modules.get(document)
+    }
+
+    /// The generated type which a field of schema `schema` refers to, either
+    /// directly or as the items of an array.
+    fn doc_link(&self, schema: &Schema) -> Option<String> {
+        let ref_ = reference(schema).or_else(|| {
+            let items = schema
+                .items
+                .first()
+                .filter(|_| schema.type_ == [SimpleTypes::Array])?;
+            reference(items)
+        })?;
+        Some(self.type_ref(ref_))
+    }
+
+    /// The entries of a map of schemas, sorted by name unless
+    /// `preserve_order` is set.
+    fn ordered<'s, I>(&self, map: I) -> Vec<(&'s String, &'s Schema)>
+    where
+        I: IntoIterator<Item = (&'s String, &'s Schema)>,
+    {
+        let mut entries = map.into_iter().collect::<Vec<_>>();
+        if !self.options.preserve_order {
+            entries.sort_by(|l, r| l.0.cmp(r.0));
+        }
+        entries
+    }
+
+    /// Adds the lifetime to a reference to a generated type which borrows
+    /// from the input.
+    fn type_use(&self, name: String) -> String {
+        if self.borrowed.contains(&name) {
+            format!("{}<'a>", name)
+        } else {
+            name
+        }
+    }
+
+    /// The generics of the type `name` with the fields or variants `body`,
+    /// `<'a>` if they borrow from the input.
+    fn generics(&mut self, name: &str, body: &TokenStream) -> Option<TokenStream> {
+        if self.options.borrow && body.to_string().contains("'a") {
+            self.borrowed.insert(name.to_string());
+            Some(quote!(<'a>))
+        } else {
+            None
+        }
+    }
+
+    /// The configured `recursion_pointer` as it is written in the types of the
+    /// fields.
+    fn recursion_pointer(&self) -> String {
+        path_string(&self.options.recursion_pointer)
+    }
+
+    /// The paths of the configured `base_derives`.
+    fn base_derives(&self) -> Vec<TokenStream> {
+        self.options
+            .base_derives
+            .iter()
+            .map(|derive| quote!(#derive))
+            .collect()
+    }
+
+    /// The `#[derive]` of `derives` and of the `serde` traits `serde_traits`,
+    /// which are told where `serde` is if it is not at the default path.
+    fn derive(&self, derives: &[TokenStream], serde_traits: &[&str]) -> TokenStream {
+        let serde_traits = serde_traits.iter().map(|name| format_ident!("{}", name));
+        if self.options.serde_path == "serde" {
+            return quote!(#[derive(#(#derives,)* #(#serde_traits),*)]);
+        }
+        let path = self.options.serde_path.parse::<TokenStream>().unwrap();
+        let serde_path = &self.options.serde_path;
+        quote! {
+            #[derive(#(#derives,)* #(#path::#serde_traits),*)]
+            #[serde(crate = #serde_path)]
+        }
+    }
+
+    /// Counts a type or field which is generated in the report of `expand`.
+    fn count(&self, f: impl FnOnce(&mut GenerationReport)) {
+        let mut report = self.report.get();
+        f(&mut report);
+        self.report.set(report);
+    }
+
+    /// The path of the item `name` of `serde_json`.
+    fn serde_json(&self, name: &str) -> String {
+        format!("{}::{}", self.options.serde_json_path, name)
+    }
+
+    /// The path of the `is_empty` method of the collection type `typ`.
+    fn is_empty_fn(&self, typ: &str) -> Option<String> {
+        let index_map = format!("{}IndexMap<", self.schemafy_path);
+        [
+            "Vec<",
+            "::std::collections::BTreeSet<",
+            "::std::collections::BTreeMap<",
+            &index_map,
+        ]
+        .iter()
+        .find(|prefix| typ.starts_with(*prefix))
+        .map(|prefix| format!("{}::is_empty", prefix.trim_end_matches('<')))
+    }
+
+    /// Adds the configured prefix and suffix to the name of a generated type.
+    fn type_name(&self, name: &str) -> String {
+        format!(
+            "{}{}{}",
+            self.options.type_prefix, name, self.options.type_suffix
+        )
+    }
+
+    fn schema(&self, schema: &'r Schema) -> Resolved<'r> {
+        let mut schema = match reference(schema) {
+            Some(ref_) => self.schema_ref(&self.resolve_ref(ref_)),
+            None => Resolved::Borrowed(schema),
+        };
+        // `resolve_ref` stops at the aliases of a `$ref` into a definition
+        if let Some(ref_) = reference(&schema).filter(|_| is_ref_alias(&schema)) {
+            let target = self.schema_ref(&self.resolve_ref(ref_));
+            schema = target;
+        }
+        let all_of = match &schema.all_of {
+            Some(all_of) if !all_of.is_empty() => all_of,
+            _ => return schema,
+        };
+        let key = &*schema as *const Schema;
+        if let Some(Some(merged)) = self.merged.borrow().get(&key) {
+            return Resolved::Owned(merged.clone());
+        }
+        let mut result = Schema::clone(&self.schema(&all_of[0]));
+        for def in &all_of[1..] {
+            merge_all_of(&mut result, &self.schema(def));
+        }
+        let result = Rc::new(result);
+        // Only the subschemas of `root` are cached, the address of a temporary may be
+        // reused by another schema
+        if let Some(entry) = self.merged.borrow_mut().get_mut(&key) {
+            *entry = Some(result.clone());
+        }
+        Resolved::Owned(result)
+    }
+
+    fn schema_ref(&self, s: &str) -> Resolved<'r> {
+        if self.ref_module(s).is_some() {
+            // Types from other documents are generated from those documents
+            static EXTERNAL: OnceLock<Schema> = OnceLock::new();
+            return Resolved::Borrowed(
+                EXTERNAL.get_or_init(|| serde_json::from_str("{}").unwrap()),
+            );
+        }
+        self.try_schema_ref(s)
+            .unwrap_or_else(|comp| panic!("Expected definition: `{}` {}", s, comp))
+    }
+
+    /// Resolves the `$ref` `s`, or returns the segment which could not be found.
+    ///
+    /// Besides definitions the pointer may step into the `properties`,
+    /// `items` and `additionalProperties` of a schema. `Schema` keeps the
+    /// `additionalProperties` as JSON, so the subschemas in them are
+    /// deserialized the first time a `$ref` points to them and then kept by
+    /// their `$ref`.
+    fn try_schema_ref<'s>(&self, s: &'s str) -> Result<Resolved<'r>, &'s str> {
+        if let Some(schema) = self.additional_properties.borrow().get(s) {
+            return Ok(Resolved::Owned(schema.clone()));
+        }
+        let mut comps = s.split('/').peekable();
+        let mut value = match self.walk(self.root, &mut comps)? {
+            Step::Schema(schema) => return Ok(Resolved::Borrowed(schema)),
+            Step::AdditionalProperties(value) => value.clone(),
+        };
+        let schema = loop {
+            let schema: Schema =
+                serde_json::from_value(value).map_err(|_| "additionalProperties")?;
+            match self.walk(&schema, &mut comps)? {
+                Step::Schema(target) => break target.clone(),
+                Step::AdditionalProperties(next) => value = next.clone(),
+            }
         };
-        match schema.all_of {
-            Some(ref all_of) if !all_of.is_empty() => {
-                all_of
+        let schema = Rc::new(schema);
+        self.additional_properties
+            .borrow_mut()
+            .insert(s.to_string(), schema.clone());
+        Ok(Resolved::Owned(schema))
+    }
+
+    /// Follows the segments `comps` of a `$ref` from `schema`, up to the
+    /// `additionalProperties` which it steps into if any.
+    fn walk<'a, 's>(
+        &self,
+        mut schema: &'a Schema,
+        comps: &mut std::iter::Peekable<std::str::Split<'s, char>>,
+    ) -> Result<Step<'a>, &'s str>
+    where
+        'r: 'a,
+    {
+        while let Some(comp) = comps.next() {
+            let definitions = match (comp, &schema.components) {
+                (comp, _) if comp.ends_with('#') => {
+                    schema = self.root;
+                    continue;
+                }
+                // The segment after `definitions` is a name, even `properties` or `items`
+                ("definitions", _) => match comps.next() {
+                    Some(comp) => {
+                        schema = schema
+                            .definitions
+                            .get(&unescape_pointer_segment(comp))
+                            .ok_or(comp)?;
+                        continue;
+                    }
+                    None => break,
+                },
+                ("properties", _) => {
+                    let comp = comps.next().ok_or(comp)?;
+                    schema = schema
+                        .properties
+                        .get(&unescape_pointer_segment(comp))
+                        .ok_or(comp)?;
+                    continue;
+                }
+                ("items", _) => {
+                    let index = match comps.peek().and_then(|index| index.parse().ok()) {
+                        Some(index) => {
+                            comps.next();
+                            index
+                        }
+                        None => 0,
+                    };
+                    schema = schema.items.get(index).ok_or(comp)?;
+                    continue;
+                }
+                ("additionalProperties", _) => {
+                    return match &schema.additional_properties {
+                        Some(value) if value.is_object() => Ok(Step::AdditionalProperties(value)),
+                        _ => Err(comp),
+                    };
+                }
+                // OpenAPI keeps the schemas in `#/components/schemas`
+                ("components", Some(components)) => {
+                    if comps.next() != Some("schemas") {
+                        return Err(comp);
+                    }
+                    let comp = comps.next().ok_or(comp)?;
+                    schema = components
+                        .schemas
+                        .get(&unescape_pointer_segment(comp))
+                        .ok_or(comp)?;
+                    continue;
+                }
+                _ => &schema.definitions,
+            };
+            schema = definitions
+                .get(&unescape_pointer_segment(comp))
+                .ok_or(comp)?;
+        }
+        Ok(Step::Schema(schema))
+    }
+
+    /// Checks that all the `$ref`s of the schema can be resolved, which
+    /// [`expand`](Self::expand) otherwise panics on.
+    ///
+    /// The definitions skipped by `x-rust-skip` may only be referred to by
+    /// other skipped definitions, as their types are not generated.
+    pub fn check_refs(&self, schema: &Schema) -> Result<(), SchemafyError> {
+        let mut result = Ok(());
+        let mut skipped = Vec::<String>::new();
+        walk_schema("#".to_string(), schema, &mut |path, schema| {
+            let is_skipped = skipped.iter().any(|skipped| {
+                path.strip_prefix(skipped.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+            });
+            if schema.x_rust_skip == Some(true) && !is_skipped {
+                skipped.push(path.to_string());
+                return;
+            }
+            match reference(schema) {
+                Some(ref_) if result.is_ok() && self.ref_module(ref_).is_none() => {
+                    match self.try_schema_ref(ref_) {
+                        Err(_) => result = Err(SchemafyError::UnresolvedRef(ref_.to_string())),
+                        Ok(def) if def.x_rust_skip == Some(true) && !is_skipped => {
+                            result = Err(SchemafyError::Unsupported(format!(
+                                "`{}` refers to `{}`, which is skipped by `x-rust-skip`",
+                                path, ref_
+                            )))
+                        }
+                        Ok(_) => (),
+                    }
+                }
+                _ => (),
+            }
+        });
+        result
+    }
+
+    /// Checks that the parts of every `allOf` of the schema have a type in
+    /// common, as an `allOf` of for example an object and a string can not
+    /// be satisfied and would become `serde_json::Value`. The `$ref`s need to
+    /// be resolvable, see [`check_refs`](Self::check_refs).
+    pub fn check_all_of(&self, schema: &Schema) -> Result<(), SchemafyError> {
+        let mut result = Ok(());
+        walk_schema("#".to_string(), schema, &mut |path, schema| {
+            let all_of = match schema.all_of.as_deref() {
+                Some(all_of) if result.is_ok() => all_of,
+                _ => return,
+            };
+            let parts = std::iter::once(schema.type_.clone())
+                .chain(all_of.iter().map(|part| self.all_of_types(part)))
+                .filter(|types| !types.is_empty())
+                .collect::<Vec<_>>();
+            let common = parts
+                .iter()
+                .skip(1)
+                .fold(parts.first().cloned(), |common, types| {
+                    common.map(|common| common_types(&common, types))
+                });
+            if common.is_some_and(|common| common.is_empty()) {
+                let parts = parts
                     .iter()
-                    .skip(1)
-                    .fold(self.schema(&all_of[0]).clone(), |mut result, def| {
-                        merge_all_of(result.to_mut(), &self.schema(def));
-                        result
+                    .map(|types| {
+                        types
+                            .iter()
+                            .map(|type_| {
+                                format!(
+                                    "`{}`",
+                                    serde_json::to_value(type_).unwrap().as_str().unwrap()
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join(" or ")
                     })
+                    .collect::<Vec<_>>();
+                result = Err(SchemafyError::Unsupported(format!(
+                    "The `allOf` at `{}` can not be satisfied, its parts have the types {}",
+                    path,
+                    parts.join(" and ")
+                )));
             }
-            _ => Cow::Borrowed(schema),
-        }
+        });
+        result
     }
 
-    fn schema_ref(&self, s: &str) -> &'r Schema {
-        s.split('/').fold(self.root, |schema, comp| {
-            if comp.ends_with('#') {
-                self.root
-            } else if comp == "definitions" {
-                schema
-            } else {
-                schema
-                    .definitions
-                    .get(comp)
-                    .unwrap_or_else(|| panic!("Expected definition: `{}` {}", s, comp))
+    /// Checks that every `oneOf` of the schema is adjacently tagged if the
+    /// `one_of_tagging` is [`OneOfTagging::Adjacent`], which would otherwise
+    /// panic during expansion. The `$ref`s need to be resolvable, see
+    /// [`check_refs`](Self::check_refs).
+    pub fn check_one_of_tagging(&self, schema: &Schema) -> Result<(), SchemafyError> {
+        if self.options.one_of_tagging != OneOfTagging::Adjacent {
+            return Ok(());
+        }
+        let mut result = Ok(());
+        walk_schema("#".to_string(), schema, &mut |path, schema| {
+            if result.is_err() || reference(schema).is_some() {
+                return;
             }
-        })
+            let schema = self.schema(schema);
+            match schema.one_of.as_deref() {
+                Some(one_of) if one_of.len() >= 2 && self.adjacent_envelope(one_of).is_none() => {
+                    result = Err(SchemafyError::Unsupported(format!(
+                        "The `oneOf` at `{}` is not an adjacently tagged envelope",
+                        path
+                    )));
+                }
+                _ => (),
+            }
+        });
+        result
+    }
+
+    /// The types which a part of an `allOf` allows, empty if it allows any.
+    fn all_of_types(&self, schema: &Schema) -> Vec<SimpleTypes> {
+        match reference(schema) {
+            Some(ref_) if self.ref_module(ref_).is_none() => self
+                .schema(&self.schema_ref(&self.resolve_ref(ref_)))
+                .type_
+                .clone(),
+            Some(_) => Vec::new(),
+            None => schema
+                .all_of
+                .iter()
+                .flatten()
+                .map(|part| self.all_of_types(part))
+                .filter(|types| !types.is_empty())
+                .fold(schema.type_.clone(), |types, part| {
+                    if types.is_empty() {
+                        part
+                    } else {
+                        common_types(&types, &part)
+                    }
+                }),
+        }
     }
 
     fn expand_type(&mut self, type_name: &str, required: bool, typ: &Schema) -> FieldType {
         let saved_type = self.current_type.clone();
         let mut result = self.expand_type_(typ);
         self.current_type = saved_type;
-        if type_name.to_pascal_case() == result.typ.to_pascal_case() {
-            result.typ = format!("Box<{}>", result.typ)
+        // A required array is not defaulted to empty, unless it is the `default`
+        if required
+            && typ.default.is_none()
+            && typ.nullable != Some(true)
+            && reference(typ).is_none()
+            && typ.type_ == [SimpleTypes::Array]
+        {
+            result.default = false;
+        }
+        // The name is compared both as it is generated, which `identifier_case`
+        // may leave in another case, and in PascalCase
+        let own_type = self.type_name(&self.options.identifier_case.type_name(type_name));
+        let pascal_case_type = self.type_name(&type_name.to_pascal_case()).to_pascal_case();
+        let is_own_type = |typ: &str| {
+            let typ = without_lifetime(typ);
+            typ == own_type || typ.to_pascal_case() == pascal_case_type
+        };
+        if is_own_type(&result.typ) {
+            result.typ = format!("{}<{}>", self.recursion_pointer(), result.typ)
+        } else if let Some(inner) = result
+            .typ
+            .strip_prefix("Option<")
+            .and_then(|inner| inner.strip_suffix('>'))
+        {
+            // A nullable reference to the type itself still needs a box to have a finite size
+            if is_own_type(inner) {
+                result.typ = format!("Option<{}<{}>>", self.recursion_pointer(), inner)
+            }
+        }
+        if self.options.redact_secrets && is_secret(&self.schema(typ)) {
+            if let "String" | "&'a str" = &*result.typ {
+                result.typ = format!("{}Secret<{}>", self.schemafy_path, result.typ);
+                result.attributes.push("skip_serializing".into());
+            }
+        }
+        if let Some(integer) = self.string_integer_type(&self.schema(typ)) {
+            let codec = match &*result.typ {
+                "String" | "&'a str" => Some((integer.to_string(), "string_number")),
+                "Option<String>" | "Option<&'a str>" => {
+                    Some((format!("Option<{}>", integer), "string_number::option"))
+                }
+                _ => None,
+            };
+            if let Some((typ, codec)) = codec {
+                result.typ = typ;
+                result
+                    .attributes
+                    .push(format!(r#"with="{}{}""#, self.schemafy_path, codec));
+            }
+        }
+        if self.options.unix_timestamps && is_unix_time(&self.schema(typ)) {
+            let date_time = format!("{0}chrono::DateTime<{0}chrono::Utc>", self.schemafy_path);
+            let codec = match &*result.typ {
+                "i64" => Some((date_time, "ts_seconds")),
+                "Option<i64>" => Some((format!("Option<{}>", date_time), "ts_seconds_option")),
+                _ => None,
+            };
+            if let Some((typ, codec)) = codec {
+                result.typ = typ;
+                result.attributes.push(format!(
+                    r#"with="{}chrono::serde::{}""#,
+                    self.schemafy_path, codec
+                ));
+            }
         }
         if !required {
             if !result.default {
                 result.typ = format!("Option<{}>", result.typ);
+                // The codecs of a value have a module for the optional value
+                let codecs = [
+                    ("json_string", "json_string::option"),
+                    ("string_number", "string_number::option"),
+                    (
+                        "chrono::serde::ts_seconds",
+                        "chrono::serde::ts_seconds_option",
+                    ),
+                ];
+                for (codec, option) in codecs {
+                    let with = format!(r#"with="{}{}""#, self.schemafy_path, codec);
+                    if let Some(attr) = result.attributes.iter_mut().find(|a| **a == with) {
+                        *attr = format!(r#"with="{}{}""#, self.schemafy_path, option);
+                        result.default = true;
+                    }
+                }
             }
-            if result.typ.starts_with("Option<") {
+            if result.typ.starts_with("Option<")
+                && !result
+                    .attributes
+                    .iter()
+                    .any(|attr| attr == "skip_serializing")
+            {
                 result
                     .attributes
                     .push("skip_serializing_if=\"Option::is_none\"".into());
@@ -433,15 +1923,58 @@ impl<'r> Expander<'r> {
     }
 
     fn expand_type_(&mut self, typ: &Schema) -> FieldType {
-        if let Some(ref ref_) = typ.ref_ {
-            self.type_ref(ref_).into()
-        } else if typ.any_of.as_ref().map_or(false, |a| a.len() >= 2) {
+        if typ.nullable == Some(true) {
+            // OpenAPI 3.0 marks nullable values with `nullable: true` instead of a `null` type
+            let mut ty = typ.clone();
+            ty.nullable = None;
+            let result = self.expand_type_(&ty);
+            if result.typ.starts_with("Option<") {
+                result
+            } else {
+                FieldType {
+                    typ: format!("Option<{}>", result.typ),
+                    attributes: vec![],
+                    default: true,
+                }
+            }
+        } else if let Some(ref_) = reference(typ) {
+            let resolved = self.resolve_ref(ref_).into_owned();
+            if self.ref_module(&resolved).is_none() && is_nested_ref(&resolved) {
+                self.expand_nested_ref(&resolved)
+            } else {
+                self.type_use(self.type_ref(ref_)).into()
+            }
+        } else if typ.all_of.as_ref().is_some_and(|a| !a.is_empty()) && is_object(&self.schema(typ))
+        {
+            // An object composed inline, such as a reference with extra
+            // `required` properties, is expanded from the merged schema
+            self.expand_type_(&self.schema(typ))
+        } else if let Some(enum_) = const_enum(typ) {
+            self.expand_type_(&enum_)
+        } else if let Some(value) = without_null_branch(typ) {
+            let result = self.expand_type_(&value);
+            if result.typ.starts_with("Option<") {
+                result
+            } else {
+                FieldType {
+                    typ: format!("Option<{}>", result.typ),
+                    attributes: vec![],
+                    default: true,
+                }
+            }
+        } else if typ.any_of.as_ref().is_some_and(|a| a.len() >= 2) {
             let any_of = typ.any_of.as_ref().unwrap();
             let simple = self.schema(&any_of[0]);
             let array = self.schema(&any_of[1]);
             if !array.type_.is_empty() {
                 if let SimpleTypes::Array = array.type_[0] {
-                    if simple == self.schema(&array.items[0]) {
+                    // An array without `items` can hold anything so it is not
+                    // a list of the first type
+                    if array
+                        .items
+                        .first()
+                        .is_some_and(|item| simple == self.schema(item))
+                    {
                         return FieldType {
                             typ: format!("Vec<{}>", self.expand_type_(&any_of[0]).typ),
                             attributes: vec![format!(
@@ -453,36 +1986,67 @@ impl<'r> Expander<'r> {
                     }
                 }
             }
-            "serde_json::Value".into()
-        } else if typ.one_of.as_ref().map_or(false, |a| a.len() >= 2) {
-            let schemas = typ.one_of.as_ref().unwrap();
-            let (type_name, type_def) = self.expand_one_of(schemas);
+            if self.options.untagged_fallbacks {
+                if let Some(fallback) = self.expand_untagged_fallback(any_of) {
+                    return fallback;
+                }
+            }
+            self.serde_json("Value").into()
+        } else if typ.one_of.as_ref().is_some_and(|a| a.len() >= 2) {
+            let (type_name, type_def) = self.expand_one_of(typ);
             self.types.push((type_name.clone(), type_def));
-            type_name.into()
-        } else if typ.type_.len() == 2 {
-            if typ.type_[0] == SimpleTypes::Null || typ.type_[1] == SimpleTypes::Null {
-                let mut ty = typ.clone();
-                ty.type_.retain(|x| *x != SimpleTypes::Null);
+            self.type_use(self.type_name(&type_name)).into()
+        } else if typ.type_.len() == 2
+            && (typ.type_[0] == SimpleTypes::Null || typ.type_[1] == SimpleTypes::Null)
+        {
+            let mut ty = typ.clone();
+            ty.type_.retain(|x| *x != SimpleTypes::Null);
 
-                FieldType {
-                    typ: format!("Option<{}>", self.expand_type_(&ty).typ),
-                    attributes: vec![],
-                    default: true,
-                }
-            } else {
-                "serde_json::Value".into()
+            FieldType {
+                typ: format!("Option<{}>", self.expand_type_(&ty).typ),
+                attributes: vec![],
+                default: true,
             }
+        } else if typ.type_.len() >= 2
+            && (self.options.untagged_type_unions || self.options.untagged_fallbacks)
+        {
+            self.expand_type_union(typ)
         } else if typ.type_.len() == 1 {
             match typ.type_[0] {
                 SimpleTypes::String => {
-                    if typ.enum_.as_ref().map_or(false, |e| e.is_empty()) {
-                        "serde_json::Value".into()
+                    if let Some(content) = json_content(typ) {
+                        FieldType {
+                            typ: self.expand_type_(content).typ,
+                            attributes: vec![format!(
+                                r#"with="{}json_string""#,
+                                self.schemafy_path
+                            )],
+                            default: false,
+                        }
+                    } else if typ.enum_.as_ref().is_some_and(|e| e.is_empty()) {
+                        self.serde_json("Value").into()
+                    } else if let Some(typ) = self.string_format_type(typ) {
+                        typ.into()
+                    } else if self.is_string_newtype(typ) {
+                        let name = format!(
+                            "{}{}",
+                            self.current_type.to_pascal_case(),
+                            self.current_field.to_pascal_case()
+                        );
+                        let tokens = self.expand_string_newtype(&self.type_name(&name), typ);
+                        self.types.push((name.clone(), tokens));
+                        self.type_name(&name).into()
+                    } else if self.options.borrow {
+                        "&'a str".into()
                     } else {
                         "String".into()
                     }
                 }
-                SimpleTypes::Integer => "i64".into(),
+                SimpleTypes::Integer => integer_type(typ).into(),
                 SimpleTypes::Boolean => "bool".into(),
+                SimpleTypes::Number if self.options.ordered_floats => {
+                    format!("{}OrderedFloat<f64>", self.schemafy_path).into()
+                }
                 SimpleTypes::Number => "f64".into(),
                 // Handle objects defined inline
                 SimpleTypes::Object
@@ -496,7 +2060,7 @@ impl<'r> Expander<'r> {
                     );
                     let tokens = self.expand_schema(&name, typ);
                     self.types.push((name.clone(), tokens));
-                    name.into()
+                    self.type_use(self.type_name(&name)).into()
                 }
                 SimpleTypes::Object => {
                     let prop = match typ.additional_properties {
@@ -504,30 +2068,505 @@ impl<'r> Expander<'r> {
                             let prop = serde_json::from_value(props.clone()).unwrap();
                             self.expand_type_(&prop).typ
                         }
-                        _ => "serde_json::Value".into(),
+                        _ => self.serde_json("Value"),
                     };
-                    let result = format!("::std::collections::BTreeMap<String, {}>", prop);
+                    let key = self
+                        .map_key_type(typ)
+                        .unwrap_or_else(|| "String".to_string());
+                    let result = if self.options.preserve_order {
+                        format!("{}IndexMap<{}, {}>", self.schemafy_path, key, prop)
+                    } else {
+                        format!("::std::collections::BTreeMap<{}, {}>", key, prop)
+                    };
+                    if self.is_map_newtype(typ, &result) {
+                        let name = format!(
+                            "{}{}",
+                            self.current_type.to_pascal_case(),
+                            self.current_field.to_pascal_case()
+                        );
+                        let tokens = self.expand_map_newtype(&self.type_name(&name), &result, typ);
+                        self.types.push((name.clone(), tokens));
+                        return self.type_name(&name).into();
+                    }
                     FieldType {
                         typ: result,
                         attributes: Vec::new(),
                         default: typ.default == Some(Value::Object(Default::default())),
                     }
                 }
+                SimpleTypes::Array if tuple_items(typ).is_some() => {
+                    let saved_type = self.current_type.clone();
+                    let items = tuple_items(typ)
+                        .unwrap()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            self.current_type = format!("{}Item{}", saved_type, i);
+                            if reference(item).is_some() {
+                                self.expand_type_(item).typ
+                            } else {
+                                self.expand_type_(&self.schema(item)).typ
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    self.current_type = saved_type;
+                    match &items[..] {
+                        [item] => format!("({},)", item).into(),
+                        items => format!("({})", items.join(", ")).into(),
+                    }
+                }
                 SimpleTypes::Array => {
-                    let item_type = typ.items.get(0).map_or("serde_json::Value".into(), |item| {
+                    let item_type = typ.items.first().map_or(self.serde_json("Value"), |item| {
                         self.current_type = format!("{}Item", self.current_type);
-                        self.expand_type_(item).typ
+                        // References keep the name of their type, anything
+                        // else may need its `allOf` merged
+                        if reference(item).is_some() {
+                            self.expand_type_(item).typ
+                        } else {
+                            self.expand_type_(&self.schema(item)).typ
+                        }
                     });
-                    format!("Vec<{}>", item_type).into()
+                    let collection = if typ.unique_items
+                        && self.options.unique_item_sets
+                        && is_ordered(&item_type)
+                    {
+                        format!("::std::collections::BTreeSet<{}>", item_type)
+                    } else {
+                        format!("Vec<{}>", item_type)
+                    };
+                    // A missing array is empty, unless the schema asks for another
+                    // default or does not allow it to be empty
+                    let default = match &typ.default {
+                        Some(Value::Array(default)) => default.is_empty(),
+                        Some(_) => false,
+                        None => typ.min_items.as_ref().and_then(Value::as_u64).unwrap_or(0) == 0,
+                    };
+                    FieldType {
+                        typ: collection,
+                        attributes: Vec::new(),
+                        default,
+                    }
                 }
-                _ => "serde_json::Value".into(),
+                _ => self.serde_json("Value").into(),
             }
         } else {
-            "serde_json::Value".into()
+            self.serde_json("Value").into()
         }
     }
 
-    fn expand_one_of(&mut self, schemas: &[Schema]) -> (String, TokenStream) {
+    /// Expands an `anyOf` into an untagged enum of the alternatives which can
+    /// be named, `$ref`s after their type and scalars after their type, and
+    /// `Other` for any other value. `None` if no alternative can be named.
+    fn expand_untagged_fallback(&mut self, any_of: &[Schema]) -> Option<FieldType> {
+        let base_derives = self.base_derives();
+        let current_field = if self.current_field.is_empty() {
+            "".to_owned()
+        } else {
+            str_to_ident(&self.current_field)
+                .to_string()
+                .to_pascal_case()
+        };
+        let type_name = format!("{}{}", self.current_type, current_field);
+
+        let saved_type = self.current_type.clone();
+        let saved_field = self.current_field.clone();
+        let mut names = BTreeSet::new();
+        let mut variants = Vec::new();
+        for schema in any_of {
+            let name = match (reference(schema), schema.type_.as_slice()) {
+                (Some(ref_), _) => {
+                    let typ = self.type_ref(ref_);
+                    typ.rsplit("::").next().unwrap_or_default().to_string()
+                }
+                (None, [SimpleTypes::Boolean]) => "Boolean".to_string(),
+                (None, [SimpleTypes::Integer]) => "Integer".to_string(),
+                (None, [SimpleTypes::Number]) => "Number".to_string(),
+                (None, [SimpleTypes::String]) => "String".to_string(),
+                _ => continue,
+            };
+            let name = unique_variant(&mut names, name);
+            // Keep inline types of the variants from taking the name of the enum
+            self.current_field = format!("{}{}", current_field, name);
+            let variant_type = self
+                .expand_type_(schema)
+                .typ
+                .parse::<TokenStream>()
+                .unwrap();
+            self.current_type.clone_from(&saved_type);
+            let name = str_to_ident(&name);
+            let borrow = serde_borrow(&variant_type);
+            variants.push(quote!(#name(#borrow #variant_type)));
+        }
+        self.current_field = saved_field;
+        if variants.is_empty() {
+            return None;
+        }
+        let other = str_to_ident(&unique_variant(&mut names, "Other".to_string()));
+        let value = self.serde_json("Value").parse::<TokenStream>().unwrap();
+        variants.push(quote!(#other(#value)));
+
+        let generics = self.generics(&self.type_name(&type_name), &quote!(#(#variants)*));
+        let type_name_ident = syn::Ident::new(&self.type_name(&type_name), Span::call_site());
+        let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
+        self.count(|report| report.enums += 1);
+        self.types.push((
+            type_name.clone(),
+            quote! {
+                #derive
+                #[serde(untagged)]
+                pub enum #type_name_ident #generics {
+                    #(#variants),*
+                }
+            },
+        ));
+        Some(self.type_use(self.type_name(&type_name)).into())
+    }
+
+    /// Expands a `type` array such as `["string", "integer"]` into an
+    /// untagged enum with one variant per type.
+    fn expand_type_union(&mut self, typ: &Schema) -> FieldType {
+        let base_derives = self.base_derives();
+        // serde tries untagged variants in order so `integer` must come
+        // before `number` which would also accept integers
+        fn variant(type_: &SimpleTypes) -> (u8, &'static str) {
+            match type_ {
+                SimpleTypes::Boolean => (0, "Boolean"),
+                SimpleTypes::Integer => (1, "Integer"),
+                SimpleTypes::Number => (2, "Number"),
+                SimpleTypes::String => (3, "String"),
+                SimpleTypes::Array => (4, "Array"),
+                SimpleTypes::Object => (5, "Object"),
+                SimpleTypes::Null => (6, "Null"),
+            }
+        }
+
+        let current_field = if self.current_field.is_empty() {
+            "".to_owned()
+        } else {
+            str_to_ident(&self.current_field)
+                .to_string()
+                .to_pascal_case()
+        };
+        let type_name = format!("{}{}", self.current_type, current_field);
+
+        let mut types = typ
+            .type_
+            .iter()
+            .filter(|type_| **type_ != SimpleTypes::Null)
+            .collect::<Vec<_>>();
+        types.sort_by_key(|type_| variant(type_).0);
+        types.dedup();
+
+        let saved_type = self.current_type.clone();
+        let saved_field = self.current_field.clone();
+        let variants = types
+            .into_iter()
+            .map(|type_| {
+                let name = variant(type_).1;
+                let mut ty = typ.clone();
+                ty.type_ = vec![type_.clone()];
+                // Keep inline types of the variants from taking the name of the enum
+                self.current_field = format!("{}{}", current_field, name);
+                let variant_type = self.expand_type_(&ty).typ.parse::<TokenStream>().unwrap();
+                self.current_type.clone_from(&saved_type);
+                let name = syn::Ident::new(name, Span::call_site());
+                let borrow = serde_borrow(&variant_type);
+                quote!(#name(#borrow #variant_type))
+            })
+            .collect::<Vec<_>>();
+        self.current_field = saved_field;
+
+        let generics = self.generics(&self.type_name(&type_name), &quote!(#(#variants)*));
+        let type_name_ident = syn::Ident::new(&self.type_name(&type_name), Span::call_site());
+        let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
+        self.count(|report| report.enums += 1);
+        self.types.push((
+            type_name.clone(),
+            quote! {
+                #derive
+                #[serde(untagged)]
+                pub enum #type_name_ident #generics {
+                    #(#variants),*
+                }
+            },
+        ));
+
+        let type_name = self.type_use(self.type_name(&type_name));
+        if typ.type_.contains(&SimpleTypes::Null) {
+            FieldType {
+                typ: format!("Option<{}>", type_name),
+                attributes: vec![],
+                default: true,
+            }
+        } else {
+            type_name.into()
+        }
+    }
+
+    /// The type of a string with a `format` which `string_formats` knows.
+    fn string_format_type(&self, schema: &Schema) -> Option<String> {
+        if !self.options.string_formats {
+            return None;
+        }
+        let typ = match schema.format.as_deref()? {
+            "email" => format!("{}Email", self.schemafy_path),
+            "hostname" => format!("{}Hostname", self.schemafy_path),
+            "uri" => format!("{}Uri", self.schemafy_path),
+            "ipv4" => "::std::net::Ipv4Addr".to_string(),
+            "ipv6" => "::std::net::Ipv6Addr".to_string(),
+            _ => return None,
+        };
+        Some(typ)
+    }
+
+    /// The integer which a string with `format` stands for when `string_integers`
+    /// is enabled.
+    fn string_integer_type(&self, schema: &Schema) -> Option<&'static str> {
+        if !self.options.string_integers || !schema.type_.contains(&SimpleTypes::String) {
+            return None;
+        }
+        match schema.format.as_deref()? {
+            "int64" => Some("i64"),
+            "uint64" => Some("u64"),
+            _ => None,
+        }
+    }
+
+    fn is_string_newtype(&self, typ: &Schema) -> bool {
+        self.options.string_length_validation
+            && typ.type_ == [SimpleTypes::String]
+            && (typ.min_length.is_some() || typ.max_length.is_some())
+    }
+
+    /// Expands a string with `minLength`/`maxLength` into a newtype which
+    /// checks the length when it is constructed or deserialized.
+    fn expand_string_newtype(&self, name: &str, typ: &Schema) -> TokenStream {
+        self.count(|report| report.structs += 1);
+        let base_derives = self.base_derives();
+        let name = syn::Ident::new(name, Span::call_site());
+        let validate = self.validate_path();
+        let bound = |bound: Option<u64>| match bound {
+            Some(bound) => quote!(Some(#bound)),
+            None => quote!(None),
+        };
+        let min = bound(typ.min_length.as_ref().and_then(Value::as_u64));
+        let max = bound(typ.max_length.map(|max| max as u64));
+        let derive = self.derive(&base_derives, &["Serialize"]);
+        let serde = self.options.serde_path.parse::<TokenStream>().unwrap();
+        quote! {
+            #derive
+            #[serde(transparent)]
+            pub struct #name(String);
+
+            impl #name {
+                pub fn new(value: String) -> Result<Self, #validate::ValidationError> {
+                    #validate::string_length(&value, #min, #max)?;
+                    Ok(#name(value))
+                }
+            }
+
+            impl ::std::ops::Deref for #name {
+                type Target = String;
+                fn deref(&self) -> &String {
+                    &self.0
+                }
+            }
+
+            impl<'de> #serde::Deserialize<'de> for #name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: #serde::Deserializer<'de>,
+                {
+                    let value = <String as #serde::Deserialize>::deserialize(deserializer)?;
+                    #name::new(value).map_err(#serde::de::Error::custom)
+                }
+            }
+        }
+    }
+
+    /// The enum which the keys of the map `typ` are when `typed_map_keys` is
+    /// enabled and its `propertyNames` is an enum of strings, generated from
+    /// the `propertyNames` unless it refers to a definition.
+    fn map_key_type(&mut self, typ: &Schema) -> Option<String> {
+        let names = typ
+            .property_names
+            .as_deref()
+            .filter(|_| self.options.typed_map_keys)?;
+        let resolved = self.schema(names);
+        let is_string_enum = resolved
+            .enum_
+            .as_ref()
+            .is_some_and(|e| !e.is_empty() && e.iter().all(Value::is_string));
+        if !is_string_enum {
+            return None;
+        }
+        if reference(names).is_some() {
+            return Some(self.expand_type_(names).typ);
+        }
+        let resolved = resolved.clone();
+        let name = format!(
+            "{}{}Key",
+            self.current_type.to_pascal_case(),
+            self.current_field.to_pascal_case()
+        );
+        let tokens = self.expand_schema(&name, &resolved);
+        self.types.push((name.clone(), tokens));
+        Some(self.type_use(self.type_name(&name)))
+    }
+
+    fn is_map_newtype(&self, typ: &Schema, map: &str) -> bool {
+        // A newtype can not borrow from the input through `&'a str`
+        self.options.map_size_validation
+            && (typ.min_properties.is_some() || typ.max_properties.is_some())
+            && !map.contains("'a")
+    }
+
+    /// Expands a map with `minProperties`/`maxProperties` into a newtype
+    /// which checks the number of entries when it is constructed or
+    /// deserialized.
+    fn expand_map_newtype(&self, name: &str, map: &str, typ: &Schema) -> TokenStream {
+        self.count(|report| report.structs += 1);
+        let base_derives = self.base_derives();
+        let name = syn::Ident::new(name, Span::call_site());
+        let map = map.parse::<TokenStream>().unwrap();
+        let validate = self.validate_path();
+        let bound = |bound: Option<u64>| match bound {
+            Some(bound) => quote!(Some(#bound)),
+            None => quote!(None),
+        };
+        let min = bound(typ.min_properties.as_ref().and_then(Value::as_u64));
+        let max = bound(typ.max_properties.map(|max| max as u64));
+        let derive = self.derive(&base_derives, &["Serialize"]);
+        let serde = self.options.serde_path.parse::<TokenStream>().unwrap();
+        quote! {
+            #derive
+            #[serde(transparent)]
+            pub struct #name(#map);
+
+            impl #name {
+                pub fn new(value: #map) -> Result<Self, #validate::ValidationError> {
+                    #validate::properties_count(value.len(), #min, #max)?;
+                    Ok(#name(value))
+                }
+            }
+
+            impl ::std::ops::Deref for #name {
+                type Target = #map;
+                fn deref(&self) -> &#map {
+                    &self.0
+                }
+            }
+
+            impl IntoIterator for #name {
+                type Item = <#map as IntoIterator>::Item;
+                type IntoIter = <#map as IntoIterator>::IntoIter;
+                fn into_iter(self) -> Self::IntoIter {
+                    self.0.into_iter()
+                }
+            }
+
+            impl<'a> IntoIterator for &'a #name {
+                type Item = <&'a #map as IntoIterator>::Item;
+                type IntoIter = <&'a #map as IntoIterator>::IntoIter;
+                fn into_iter(self) -> Self::IntoIter {
+                    self.0.iter()
+                }
+            }
+
+            impl<'de> #serde::Deserialize<'de> for #name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: #serde::Deserializer<'de>,
+                {
+                    let value = <#map as #serde::Deserialize>::deserialize(deserializer)?;
+                    #name::new(value).map_err(#serde::de::Error::custom)
+                }
+            }
+        }
+    }
+
+    /// Expands the type of the subschema inside of a definition which the
+    /// `$ref` `s` points to, such as `#/definitions/foo/properties/bar`.
+    ///
+    /// The subschema is expanded under the same names as when its definition
+    /// is, so that it stands for the same type. The types which it generates
+    /// are left out as the definition generates them already.
+    fn expand_nested_ref(&mut self, s: &str) -> FieldType {
+        let target = self.schema_ref(s);
+        let saved_type = std::mem::take(&mut self.current_type);
+        let saved_field = std::mem::take(&mut self.current_field);
+        let (types, report) = (self.types.len(), self.report.get());
+        if let Some(root_name) = self.root_name {
+            self.current_type = self.options.identifier_case.type_name(root_name);
+        }
+        let mut in_items = false;
+        let pointer = s.split_once('#').map_or("", |(_, pointer)| pointer);
+        let mut comps = pointer
+            .split('/')
+            .filter(|comp| !comp.is_empty())
+            .peekable();
+        while let Some(comp) = comps.next() {
+            in_items = false;
+            match comp {
+                "definitions" | "components" if comps.peek().is_some() => {
+                    if comp == "components" {
+                        comps.next();
+                    }
+                    let name = unescape_pointer_segment(comps.next().unwrap());
+                    self.current_type = self.options.identifier_case.type_name(&name);
+                    self.current_field.clear();
+                }
+                "properties" => {
+                    // The property of an object defined inline is a field of its own struct
+                    if !self.current_field.is_empty() {
+                        let name = format!(
+                            "{}{}",
+                            self.current_type.to_pascal_case(),
+                            self.current_field.to_pascal_case()
+                        );
+                        self.current_type = self.options.identifier_case.type_name(&name);
+                    }
+                    self.current_field = comps
+                        .next()
+                        .map(unescape_pointer_segment)
+                        .unwrap_or_default();
+                }
+                "items" => {
+                    if comps
+                        .peek()
+                        .is_some_and(|index| index.parse::<usize>().is_ok())
+                    {
+                        comps.next();
+                    }
+                    self.current_type.push_str("Item");
+                    in_items = true;
+                }
+                "additionalProperties" => (),
+                name => {
+                    self.current_type = self
+                        .options
+                        .identifier_case
+                        .type_name(&unescape_pointer_segment(name));
+                    self.current_field.clear();
+                }
+            }
+        }
+        // Array items are expanded with their `allOf` merged, as in `expand_type_`
+        let result = if in_items && reference(&target).is_none() {
+            self.expand_type_(&self.schema(&target))
+        } else {
+            self.expand_type_(&target)
+        };
+        self.types.truncate(types);
+        self.report.set(report);
+        self.current_type = saved_type;
+        self.current_field = saved_field;
+        result
+    }
+
+    fn expand_one_of(&mut self, typ: &Schema) -> (String, TokenStream) {
+        let base_derives = self.base_derives();
+        let schemas = typ.one_of.as_deref().unwrap_or_default();
         let current_field = if self.current_field.is_empty() {
             "".to_owned()
         } else {
@@ -539,35 +2578,199 @@ impl<'r> Expander<'r> {
         if schemas.is_empty() {
             return (saved_type, TokenStream::new());
         }
-        let (variant_names, variant_types): (Vec<_>, Vec<_>) = schemas
+        match self.options.one_of_tagging {
+            OneOfTagging::Untagged => (),
+            OneOfTagging::Auto | OneOfTagging::Adjacent => {
+                if let Some(envelope) = self.adjacent_envelope(schemas) {
+                    let type_def = self.expand_adjacently_tagged(&saved_type, typ, envelope);
+                    return (saved_type, type_def);
+                }
+                if self.options.one_of_tagging == OneOfTagging::Adjacent {
+                    panic!(
+                        "The `oneOf` of `{}` is not an adjacently tagged envelope",
+                        saved_type
+                    );
+                }
+            }
+        }
+        let variants = schemas
             .iter()
             .enumerate()
             .map(|(i, schema)| {
                 let name = schema.id.clone().unwrap_or_else(|| format!("Variant{}", i));
-                if let Some(ref_) = &schema.ref_ {
-                    let type_ = self.type_ref(ref_);
-                    (format_ident!("{}", &name), format_ident!("{}", &type_))
+                let variant_name = format_ident!("{}", &name);
+                if let Some(ref_) = reference(schema) {
+                    let type_ = self.boxed_variant(schema, self.type_use(self.type_ref(ref_)));
+                    let type_ = type_.parse::<TokenStream>().unwrap();
+                    let borrow = serde_borrow(&type_);
+                    quote!(#variant_name(#borrow #type_))
+                } else if !self.options.box_variants && !self.schema(schema).properties.is_empty() {
+                    // Struct shaped variants are expanded in place instead of
+                    // through a separate struct definition
+                    self.expand_definitions(schema);
+                    self.current_type = format!("{}{}", saved_type, &name);
+                    let fields = FieldExpander {
+                        default: true,
+                        visibility: "",
+                        optional: Vec::new(),
+                        validations: Vec::new(),
+                        default_fns: Vec::new(),
+                        expander: self,
+                    }
+                    .expand_fields(&saved_type, schema);
+                    quote!(#variant_name { #(#fields),* })
                 } else {
                     let type_name = format!("{}{}", saved_type, &name);
                     let field_type = self.expand_schema(&type_name, schema);
                     self.types.push((type_name.clone(), field_type));
-                    (format_ident!("{}", &name), format_ident!("{}", &type_name))
+                    let type_ =
+                        self.boxed_variant(schema, self.type_use(self.type_name(&type_name)));
+                    let type_ = type_.parse::<TokenStream>().unwrap();
+                    let borrow = serde_borrow(&type_);
+                    quote!(#variant_name(#borrow #type_))
                 }
             })
-            .unzip();
-        let type_name_ident = syn::Ident::new(&saved_type, Span::call_site());
+            .collect::<Vec<_>>();
+        let generics = self.generics(&self.type_name(&saved_type), &quote!(#(#variants)*));
+        let type_name_ident = syn::Ident::new(&self.type_name(&saved_type), Span::call_site());
+        let schema_const = self.schema_const(&type_name_ident, &generics, typ);
+        let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
+        self.count(|report| report.enums += 1);
         let type_def = quote! {
-            #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+            #derive
             #[serde(untagged)]
-            pub enum #type_name_ident {
-                #(#variant_names(#variant_types)),*
+            pub enum #type_name_ident #generics {
+                #(#variants),*
             }
+            #schema_const
         };
         (saved_type, type_def)
     }
 
+    /// The type of a `oneOf` variant of schema `schema`, boxed if it is a
+    /// struct and `box_variants` is enabled.
+    fn boxed_variant(&self, schema: &Schema, typ: String) -> String {
+        if self.options.box_variants && !self.schema(schema).properties.is_empty() {
+            format!("Box<{}>", typ)
+        } else {
+            typ
+        }
+    }
+
+    /// If every variant of a `oneOf` is an object of only a tag property with
+    /// a single string value and a content property, the same in all variants,
+    /// returns the names of those properties and the tag value and content of
+    /// each variant.
+    fn adjacent_envelope(&self, schemas: &[Schema]) -> Option<AdjacentEnvelope> {
+        fn tag_value(property: &Schema) -> Option<&str> {
+            match property.enum_.as_deref() {
+                Some([Value::String(value)]) => Some(value),
+                _ => None,
+            }
+        }
+
+        let mut envelope = AdjacentEnvelope {
+            tag: String::new(),
+            content: String::new(),
+            variants: Vec::new(),
+        };
+        for schema in schemas {
+            let schema = match reference(schema) {
+                Some(ref_) => self.schema_ref(ref_),
+                None => Resolved::Borrowed(schema),
+            };
+            let required = schema.required.as_deref().unwrap_or_default();
+            if schema.properties.len() != 2 || required.len() != 2 {
+                return None;
+            }
+            let mut properties = schema.properties.iter();
+            let (first, second) = (properties.next()?, properties.next()?);
+            let ((tag, value), (content, content_schema)) =
+                match (tag_value(first.1), tag_value(second.1)) {
+                    (Some(value), None) => ((first.0, value), second),
+                    (None, Some(value)) => ((second.0, value), first),
+                    _ => return None,
+                };
+            if !required.contains(tag) || !required.contains(content) {
+                return None;
+            }
+            if envelope.variants.is_empty() {
+                envelope.tag.clone_from(tag);
+                envelope.content.clone_from(content);
+            } else if envelope.tag != *tag || envelope.content != *content {
+                return None;
+            }
+            envelope
+                .variants
+                .push((value.to_string(), content_schema.clone()));
+        }
+        Some(envelope)
+    }
+
+    fn expand_adjacently_tagged(
+        &mut self,
+        name: &str,
+        typ: &Schema,
+        envelope: AdjacentEnvelope,
+    ) -> TokenStream {
+        let base_derives = self.base_derives();
+        let saved_field = std::mem::take(&mut self.current_field);
+        let mut variant_names = BTreeSet::new();
+        let mut tags = Vec::new();
+        let variants = envelope
+            .variants
+            .iter()
+            .map(|(value, content)| {
+                let variant = unique_variant(&mut variant_names, value.to_pascal_case());
+                self.current_type = format!("{}{}", name, variant);
+                let type_ = self.expand_type_(content).typ;
+                let type_ = self.boxed_variant(content, type_);
+                let type_ = type_.parse::<TokenStream>().unwrap();
+                let borrow = serde_borrow(&type_);
+                let variant = str_to_ident(&variant);
+                tags.push(quote!(Self::#variant(_) => #value));
+                let rename = if variant == value {
+                    None
+                } else {
+                    Some(quote!(#[serde(rename = #value)]))
+                };
+                quote! {
+                    #rename
+                    #variant(#borrow #type_)
+                }
+            })
+            .collect::<Vec<_>>();
+        self.current_field = saved_field;
+        let generics = self.generics(&self.type_name(name), &quote!(#(#variants)*));
+        let type_name_ident = syn::Ident::new(&self.type_name(name), Span::call_site());
+        let schema_const = self.schema_const(&type_name_ident, &generics, typ);
+        let (tag, content) = (&envelope.tag, &envelope.content);
+        let tag_doc = format!("The `{}` of the variant.", tag);
+        let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
+        self.count(|report| report.enums += 1);
+        quote! {
+            #derive
+            #[serde(tag = #tag, content = #content)]
+            pub enum #type_name_ident #generics {
+                #(#variants),*
+            }
+            impl #generics #type_name_ident #generics {
+                #[doc = #tag_doc]
+                pub fn tag(&self) -> &'static str {
+                    match self {
+                        #(#tags),*
+                    }
+                }
+            }
+            #schema_const
+        }
+    }
+
     fn expand_definitions(&mut self, schema: &Schema) {
-        for (name, def) in &schema.definitions {
+        for (name, def) in self.ordered(definitions(schema)) {
+            if def.x_rust_skip == Some(true) {
+                continue;
+            }
             let type_decl = self.expand_schema(name, def);
             let definition_tokens = match def.description {
                 Some(ref comment) => {
@@ -584,21 +2787,53 @@ impl<'r> Expander<'r> {
     }
 
     fn expand_schema(&mut self, original_name: &str, schema: &Schema) -> TokenStream {
+        let base_derives = self.base_derives();
+        let const_enum_schema;
+        let schema = match const_enum(schema) {
+            Some(enum_) => {
+                const_enum_schema = enum_;
+                &const_enum_schema
+            }
+            None => schema,
+        };
         self.expand_definitions(schema);
+        self.check_examples(original_name, schema);
 
-        let pascal_case_name = replace_invalid_identifier_chars(&original_name.to_pascal_case());
+        let pascal_case_name = self.options.identifier_case.type_name(original_name);
         self.current_type.clone_from(&pascal_case_name);
-        let (fields, default) = {
+        if is_ref_alias(schema) {
+            // The fields of the referenced type are not expanded again
+            let type_name = self.type_name(&pascal_case_name);
+            return self.expand_alias(&type_name, schema);
+        }
+        let (fields, default, optional, mut validations, default_fns) = {
             let mut field_expander = FieldExpander {
-                default: true,
+                // `x-rust-no-default` keeps a struct of optional fields from deriving `Default`
+                default: schema.x_rust_no_default != Some(true),
+                visibility: "pub",
+                optional: Vec::new(),
+                validations: Vec::new(),
+                default_fns: Vec::new(),
                 expander: self,
             };
             let fields = field_expander.expand_fields(original_name, schema);
-            (fields, field_expander.default)
+            (
+                fields,
+                field_expander.default,
+                field_expander.optional,
+                field_expander.validations,
+                field_expander.default_fns,
+            )
         };
-        let name = syn::Ident::new(&pascal_case_name, Span::call_site());
+        let type_name = self.type_name(&pascal_case_name);
+        let name = syn::Ident::new(&type_name, Span::call_site());
         let is_struct =
             !fields.is_empty() || schema.additional_properties == Some(Value::Bool(false));
+        let generics = if is_struct {
+            self.generics(&type_name, &quote!(#(#fields)*))
+        } else {
+            None
+        };
         let serde_rename = if name == original_name {
             None
         } else {
@@ -606,8 +2841,102 @@ impl<'r> Expander<'r> {
                 #[serde(rename = #original_name)]
             })
         };
-        let is_enum = schema.enum_.as_ref().map_or(false, |e| !e.is_empty());
+        let is_enum = schema.enum_.as_ref().is_some_and(|e| !e.is_empty());
+        let has_null = schema.enum_.iter().flatten().any(Value::is_null);
+        let schema_const = if is_enum && has_null {
+            // The `Option` alias can't have an impl so the constant goes on the enum
+            let enum_name = syn::Ident::new(&format!("{}_", name), Span::call_site());
+            let schema_const = self.schema_const(&enum_name, &None, schema);
+            let bound_constants = self.bound_constants(&enum_name, &None, schema);
+            quote!(#schema_const #bound_constants)
+        } else {
+            let schema_const = self.schema_const(&name, &generics, schema);
+            let bound_constants = self.bound_constants(&name, &generics, schema);
+            quote!(#schema_const #bound_constants)
+        };
+        let mut validate = None;
+        let mut from_str = None;
+        let mut apply_patch = None;
         let type_decl = if is_struct {
+            self.count(|report| report.structs += 1);
+            let dependencies = dependencies(&self.schema(schema));
+            let dependency_docs = dependency_docs(&dependencies);
+            if self.options.validation {
+                if let Some(not) = &schema.not {
+                    let validate = self.validate_path();
+                    let not = self.not_json(not);
+                    validations.push(quote! {
+                        if let Err(err) = #validate::not(self, #not) {
+                            errors.push(err);
+                        }
+                    });
+                }
+                validate =
+                    Some(self.validate(&name, &generics, &dependencies, &optional, &validations));
+                self.validated.insert(type_name.clone());
+            } else if self.options.dependency_validation {
+                validate = self.dependency_validate(&name, &generics, &dependencies, &optional);
+            }
+            let redacted_debug = if self.options.redact_debug {
+                self.redacted_debug(&name, &generics, schema, &base_derives)
+            } else {
+                None
+            };
+            let read_only_eq = if self.options.eq_ignore_read_only {
+                self.read_only_eq(&name, &generics, schema, &base_derives)
+            } else {
+                None
+            };
+            let default_fns = if default_fns.is_empty() {
+                None
+            } else {
+                Some(quote! {
+                    impl #generics #name #generics {
+                        #(#default_fns)*
+                    }
+                })
+            };
+            // The derives which are replaced by an impl of their own
+            let base_derives = base_derives
+                .into_iter()
+                .filter(|derive| {
+                    !(redacted_debug.is_some() && is_derive(derive, "Debug")
+                        || read_only_eq.is_some() && is_derive(derive, "PartialEq"))
+                })
+                .collect::<Vec<_>>();
+            if self.options.json_from_str && generics.is_none() {
+                let serde_json = self.options.serde_json_path.parse::<TokenStream>().unwrap();
+                from_str = Some(quote! {
+                    impl ::std::str::FromStr for #name {
+                        type Err = #serde_json::Error;
+
+                        fn from_str(s: &str) -> Result<Self, Self::Err> {
+                            #serde_json::from_str(s)
+                        }
+                    }
+                });
+            }
+            if self.options.merge_patch && generics.is_none() {
+                let serde_json = self.options.serde_json_path.parse::<TokenStream>().unwrap();
+                let merge_patch = format!("{}merge_patch", self.schemafy_path)
+                    .parse::<TokenStream>()
+                    .unwrap();
+                apply_patch = Some(quote! {
+                    impl #name {
+                        /// Applies the JSON Merge Patch `patch`, where a `null` member removes
+                        /// an optional field.
+                        pub fn apply_patch(
+                            &mut self,
+                            patch: &#serde_json::Value,
+                        ) -> Result<(), #serde_json::Error> {
+                            let mut value = #serde_json::to_value(&*self)?;
+                            #merge_patch::apply(&mut value, patch);
+                            *self = #serde_json::from_value(value)?;
+                            Ok(())
+                        }
+                    }
+                });
+            }
             let serde_deny_unknown = if schema.additional_properties == Some(Value::Bool(false))
                 && schema.pattern_properties.is_empty()
             {
@@ -615,107 +2944,155 @@ impl<'r> Expander<'r> {
             } else {
                 None
             };
+            let mut visited = Vec::new();
+            if let Some(visitor) = self.options.visitor {
+                visitor.on_struct(&type_name, schema, &mut visited);
+            }
             if default {
+                self.defaults.insert(type_name.clone());
+                let serde_default = if self.options.serde_default_container {
+                    Some(quote! { #[serde(default)] })
+                } else {
+                    None
+                };
+                let derive = self.derive(
+                    &[base_derives.as_slice(), &[quote!(Default)]].concat(),
+                    &["Deserialize", "Serialize"],
+                );
                 quote! {
-                    #[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+                    #derive
+                    #(#dependency_docs)*
                     #serde_rename
+                    #serde_default
                     #serde_deny_unknown
-                    pub struct #name {
+                    #(#visited)*
+                    pub struct #name #generics {
                         #(#fields),*
                     }
+                    #redacted_debug
+                    #read_only_eq
+                    #default_fns
                 }
             } else {
+                let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
                 quote! {
-                    #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+                    #derive
+                    #(#dependency_docs)*
                     #serde_rename
                     #serde_deny_unknown
-                    pub struct #name {
+                    #(#visited)*
+                    pub struct #name #generics {
                         #(#fields),*
                     }
+                    #redacted_debug
+                    #read_only_eq
+                    #default_fns
                 }
             }
         } else if is_enum {
+            self.count(|report| report.enums += 1);
+            let base_derives = unit_enum_derives(base_derives, self.options.typed_map_keys);
             let mut optional = false;
             let mut repr_i64 = false;
-            let variants = if schema.enum_names.as_ref().map_or(false, |e| !e.is_empty()) {
-                let values = schema.enum_.as_ref().map_or(&[][..], |v| v);
-                let names = schema.enum_names.as_ref().map_or(&[][..], |v| v);
-                if names.len() != values.len() {
-                    panic!(
-                        "enumNames(length {}) and enum(length {}) have different length",
-                        names.len(),
-                        values.len()
-                    )
-                }
-                names
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, name)| (&values[idx], name))
-                    .flat_map(|(value, name)| {
-                        let pascal_case_variant = name.to_pascal_case();
-                        let variant_name =
-                            rename_keyword("", &pascal_case_variant).unwrap_or_else(|| {
-                                let v = syn::Ident::new(&pascal_case_variant, Span::call_site());
-                                quote!(#v)
-                            });
-                        match value {
-                            Value::String(ref s) => Some(quote! {
+            let descriptions = &schema.x_enum_descriptions;
+            let variant_doc = |idx: usize| {
+                descriptions
+                    .get(idx)
+                    .filter(|doc| !doc.is_empty())
+                    .map(|doc| make_doc_comment(doc, LINE_LENGTH - INDENT_LENGTH))
+            };
+            let has_names = enum_names(schema).is_some();
+            let screaming_snake = is_screaming_snake_enum(schema);
+            let serde_rename_all = if screaming_snake {
+                Some(quote! { #[serde(rename_all = "SCREAMING_SNAKE_CASE")] })
+            } else {
+                None
+            };
+            let values = schema.enum_.as_deref().unwrap_or_default();
+            let mut str_arms = Vec::new();
+            let mut variants = enum_variant_names(schema)
+                .into_iter()
+                .zip(values)
+                .enumerate()
+                .flat_map(|(idx, (name, value))| {
+                    let doc = variant_doc(idx);
+                    let variant = match value {
+                        // Without `enumNames` the name is the value itself
+                        Value::String(ref s)
+                            if !screaming_snake && (has_names || name.as_deref() != Some(s)) =>
+                        {
+                            let variant_name = variant_ident(name.as_ref()?);
+                            str_arms.push(quote!(Self::#variant_name => #s));
+                            quote! {
+                                #doc
                                 #[serde(rename = #s)]
                                 #variant_name
-                            }),
-                            Value::Number(ref n) => {
-                                repr_i64 = true;
-                                let num = syn::LitInt::new(&n.to_string(), Span::call_site());
-                                Some(quote! {
-                                    #variant_name = #num
-                                })
                             }
-                            Value::Null => {
-                                optional = true;
-                                None
+                        }
+                        Value::String(ref s) => {
+                            let variant_name = variant_ident(name.as_ref()?);
+                            str_arms.push(quote!(Self::#variant_name => #s));
+                            quote! {
+                                #doc
+                                #variant_name
                             }
-                            _ => panic!("Expected string,bool or number for enum got `{}`", value),
                         }
-                    })
-                    .collect::<Vec<_>>()
-            } else {
-                schema
-                    .enum_
-                    .as_ref()
-                    .map_or(&[][..], |v| v)
-                    .iter()
-                    .flat_map(|v| match *v {
-                        Value::String(ref v) => {
-                            let pascal_case_variant = v.to_pascal_case();
-                            let variant_name = rename_keyword("", &pascal_case_variant)
-                                .unwrap_or_else(|| {
-                                    let v =
-                                        syn::Ident::new(&pascal_case_variant, Span::call_site());
-                                    quote!(#v)
-                                });
-                            Some(if pascal_case_variant == *v {
-                                variant_name
-                            } else {
-                                quote! {
-                                    #[serde(rename = #v)]
-                                    #variant_name
-                                }
-                            })
+                        Value::Number(ref n) if has_names => {
+                            repr_i64 = true;
+                            let variant_name = variant_ident(name.as_ref()?);
+                            let num = syn::LitInt::new(&n.to_string(), Span::call_site());
+                            quote! {
+                                #doc
+                                #variant_name = #num
+                            }
+                        }
+                        Value::Null => {
+                            optional = true;
+                            return None;
+                        }
+                        _ if has_names => {
+                            panic!("Expected string,bool or number for enum got `{}`", value)
+                        }
+                        _ => panic!("Expected string for enum got `{}`", value),
+                    };
+                    Some((name?, variant))
+                })
+                .collect::<Vec<_>>();
+            if self.options.sort_enum_variants {
+                variants.sort_by(|(a, _), (b, _)| a.cmp(b));
+            }
+            let variants = variants.into_iter().map(|(_, variant)| variant);
+            let enum_name = if optional {
+                syn::Ident::new(&format!("{}_", name), Span::call_site())
+            } else {
+                name.clone()
+            };
+            // Compares with the value a variant is serialized as, not its name
+            let str_eq = if self.options.str_eq && !repr_i64 {
+                Some(quote! {
+                    impl PartialEq<str> for #enum_name {
+                        fn eq(&self, other: &str) -> bool {
+                            other == match self {
+                                #(#str_arms),*
+                            }
                         }
-                        Value::Null => {
-                            optional = true;
-                            None
+                    }
+                    impl PartialEq<&str> for #enum_name {
+                        fn eq(&self, other: &&str) -> bool {
+                            self == *other
                         }
-                        _ => panic!("Expected string for enum got `{}`", v),
-                    })
-                    .collect::<Vec<_>>()
+                    }
+                })
+            } else {
+                None
             };
             if optional {
-                let enum_name = syn::Ident::new(&format!("{}_", name), Span::call_site());
+                self.count(|report| report.aliases += 1);
+                self.defaults.insert(type_name.clone());
                 if repr_i64 {
                     quote! {
                         pub type #name = Option<#enum_name>;
-                        #[derive(Clone, PartialEq, Debug, Serialize_repr, Deserialize_repr)]
+                        #[derive(#(#base_derives,)* Serialize_repr, Deserialize_repr)]
                         #serde_rename
                         #[repr(i64)]
                         pub enum #enum_name {
@@ -723,18 +3100,21 @@ impl<'r> Expander<'r> {
                         }
                     }
                 } else {
+                    let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
                     quote! {
                         pub type #name = Option<#enum_name>;
-                        #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+                        #derive
                         #serde_rename
+                        #serde_rename_all
                         pub enum #enum_name {
                             #(#variants),*
                         }
+                        #str_eq
                     }
                 }
             } else if repr_i64 {
                 quote! {
-                    #[derive(Clone, PartialEq, Debug, Serialize_repr, Deserialize_repr)]
+                    #[derive(#(#base_derives,)* Serialize_repr, Deserialize_repr)]
                     #serde_rename
                     #[repr(i64)]
                     pub enum #name {
@@ -742,50 +3122,850 @@ impl<'r> Expander<'r> {
                     }
                 }
             } else {
+                let derive = self.derive(&base_derives, &["Deserialize", "Serialize"]);
                 quote! {
-                    #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+                    #derive
                     #serde_rename
+                    #serde_rename_all
                     pub enum #name {
                         #(#variants),*
                     }
+                    #str_eq
                 }
             }
+        } else if self.is_string_newtype(schema) {
+            self.expand_string_newtype(&type_name, schema)
         } else {
-            let typ = self
-                .expand_type("", true, schema)
-                .typ
-                .parse::<TokenStream>()
-                .unwrap();
-            // Skip self-referential types, e.g. `struct Schema = Schema`
-            if name == typ.to_string() {
-                return TokenStream::new();
+            return self.expand_alias(&type_name, schema);
+        };
+        let examples = if is_struct && generics.is_none() || is_enum && !has_null {
+            self.example_fixtures(&name, original_name, schema)
+        } else {
+            None
+        };
+        quote! {
+            #type_decl
+            #schema_const
+            #validate
+            #from_str
+            #apply_patch
+            #examples
+        }
+    }
+
+    /// The constructors of `name` from the `examples` of its schema when
+    /// `example_fixtures` is enabled, `None` if it has no examples.
+    fn example_fixtures(
+        &self,
+        name: &syn::Ident,
+        original_name: &str,
+        schema: &Schema,
+    ) -> Option<TokenStream> {
+        if !self.options.example_fixtures || schema.examples.is_empty() {
+            return None;
+        }
+        self.record_example_mismatches(original_name, schema);
+        let serde_json = self.options.serde_json_path.parse::<TokenStream>().unwrap();
+        let constructors = schema.examples.iter().enumerate().map(|(i, example)| {
+            let function = format_ident!("example_{}", i);
+            let doc = format!(" The example `{}` of the schema.", example);
+            let json = example.to_string();
+            quote! {
+                #[doc = #doc]
+                pub fn #function() -> Self {
+                    #serde_json::from_str(#json).unwrap()
+                }
+            }
+        });
+        Some(quote! {
+            #[cfg(test)]
+            impl #name {
+                #(#constructors)*
+            }
+        })
+    }
+
+    /// Whether the type of a field implements `Default`, which an `Option` or a
+    /// field with `#[serde(default)]` does as well as the generated types which
+    /// derive it.
+    fn implements_default(&self, field_type: &FieldType) -> bool {
+        field_type.default
+            || field_type.typ.starts_with("Option<")
+            || self.defaults.contains(without_lifetime(&field_type.typ))
+    }
+
+    /// Generates the type alias `type_name` of the type of `schema`.
+    fn expand_alias(&mut self, type_name: &str, schema: &Schema) -> TokenStream {
+        let name = syn::Ident::new(type_name, Span::call_site());
+        let field_type = self.expand_type("", true, schema);
+        if self.implements_default(&field_type) {
+            self.defaults.insert(type_name.to_string());
+        }
+        let typ = field_type.typ;
+        let typ = typ.parse::<TokenStream>().unwrap();
+        // Skip self-referential types, e.g. `struct Schema = Schema`
+        if name == typ.to_string() {
+            return TokenStream::new();
+        }
+        self.count(|report| report.aliases += 1);
+        let generics = self.generics(type_name, &typ);
+        quote! {
+            pub type #name #generics = #typ;
+        }
+    }
+
+    /// A `Debug` impl printing `[redacted]` for the sensitive properties,
+    /// `None` if the struct has none or does not derive `Debug`.
+    fn redacted_debug(
+        &self,
+        name: &syn::Ident,
+        generics: &Option<TokenStream>,
+        schema: &Schema,
+        base_derives: &[TokenStream],
+    ) -> Option<TokenStream> {
+        let schema = self.schema(schema);
+        let properties = self.ordered(&schema.properties);
+        if !base_derives.iter().any(|derive| is_derive(derive, "Debug"))
+            || !properties
+                .iter()
+                .any(|(_, property)| is_secret(&self.schema(property)))
+        {
+            return None;
+        }
+        let fields = properties.iter().map(|(field_name, property)| {
+            let field = field_ident(field_name, self.options.identifier_case);
+            let label = field.to_string();
+            if is_secret(&self.schema(property)) {
+                quote!(.field(#label, &format_args!("[redacted]")))
+            } else {
+                quote!(.field(#label, &self.#field))
+            }
+        });
+        let label = name.to_string();
+        Some(quote! {
+            impl #generics ::std::fmt::Debug for #name #generics {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.debug_struct(#label)
+                        #(#fields)*
+                        .finish()
+                }
+            }
+        })
+    }
+
+    /// The variant which is the `default` of the property `property` of an
+    /// enum type, given either by the property or by the enum.
+    fn enum_default(&self, property: &Schema) -> Option<syn::Ident> {
+        let target = self.schema(property);
+        let target = const_enum(&target).map_or(target, |s| Resolved::Owned(Rc::new(s)));
+        let default = property.default.as_ref().or(target.default.as_ref())?;
+        let values = target.enum_.as_deref()?;
+        // A `null` value makes the type an `Option` which has a default already
+        if values.iter().any(Value::is_null) {
+            return None;
+        }
+        let index = values.iter().position(|value| value == default)?;
+        let name = enum_variant_names(&target).into_iter().nth(index)??;
+        Some(str_to_ident(&name))
+    }
+
+    /// A `PartialEq` impl which ignores the `readOnly` properties, `None` if
+    /// the struct has none or does not derive `PartialEq`.
+    fn read_only_eq(
+        &self,
+        name: &syn::Ident,
+        generics: &Option<TokenStream>,
+        schema: &Schema,
+        base_derives: &[TokenStream],
+    ) -> Option<TokenStream> {
+        let schema = self.schema(schema);
+        let properties = self.ordered(&schema.properties);
+        let is_read_only = |property: &Schema| self.schema(property).read_only == Some(true);
+        if !base_derives
+            .iter()
+            .any(|derive| is_derive(derive, "PartialEq"))
+            || !properties
+                .iter()
+                .any(|(_, property)| is_read_only(property))
+        {
+            return None;
+        }
+        let fields = properties
+            .iter()
+            .filter(|(_, property)| !is_read_only(property))
+            .map(|(field_name, _)| field_ident(field_name, self.options.identifier_case))
+            .collect::<Vec<_>>();
+        let eq = if fields.is_empty() {
+            quote!(let _ = other; true)
+        } else {
+            quote!(#(self.#fields == other.#fields)&&*)
+        };
+        Some(quote! {
+            impl #generics PartialEq for #name #generics {
+                fn eq(&self, other: &Self) -> bool {
+                    #eq
+                }
+            }
+        })
+    }
+
+    /// Generates `validate`, which checks the property `dependencies` of a
+    /// struct at runtime.
+    fn dependency_validate(
+        &self,
+        name: &syn::Ident,
+        generics: &Option<TokenStream>,
+        dependencies: &[(String, Option<Vec<String>>)],
+        optional: &[String],
+    ) -> Option<TokenStream> {
+        let validate = self.validate_path();
+        let checks = dependency_checks(dependencies, optional, self.options.identifier_case)
+            .map(|(condition, message)| {
+                quote! {
+                    if #condition {
+                        return Err(#validate::ValidationError::new(#message));
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        if checks.is_empty() {
+            return None;
+        }
+        Some(quote! {
+            impl #generics #name #generics {
+                /// Checks the `dependencies` of the schema.
+                pub fn validate(&self) -> Result<(), #validate::ValidationError> {
+                    #(#checks)*
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// The JSON of the subschema of `not`, which is interpreted when the value
+    /// is validated and so has its references replaced by what they refer to.
+    fn not_json(&self, not: &Schema) -> String {
+        fn strip_defaults(value: &mut Value) {
+            if let Value::Object(object) = value {
+                object.retain(|key, value| {
+                    let is_default = match value {
+                        Value::Array(array) => array.is_empty(),
+                        Value::Object(object) => object.is_empty(),
+                        Value::Bool(b) => !*b,
+                        _ => false,
+                    };
+                    !(is_default
+                        && [
+                            "items",
+                            "type",
+                            "definitions",
+                            "properties",
+                            "patternProperties",
+                            "examples",
+                            "x-enum-descriptions",
+                            "uniqueItems",
+                        ]
+                        .contains(&key.as_str()))
+                });
+                object
+                    .iter_mut()
+                    .filter(|(key, _)| {
+                        !["enum", "const", "default", "examples"].contains(&key.as_str())
+                    })
+                    .for_each(|(_, value)| strip_defaults(value));
+            } else if let Value::Array(array) = value {
+                array.iter_mut().for_each(strip_defaults);
+            }
+        }
+
+        let mut json = serde_json::to_value(self.inline_refs(not, INLINE_REF_DEPTH)).unwrap();
+        // `Schema` serializes its empty collections, which do not restrict anything
+        strip_defaults(&mut json);
+        json.to_string()
+    }
+
+    /// Replaces the references of `schema` by a copy of what they refer to,
+    /// recursive references are kept after `depth` levels.
+    fn inline_refs(&self, schema: &Schema, depth: usize) -> Schema {
+        if let Some(ref_) = reference(schema).filter(|_| depth > 0) {
+            if let Ok(target) = self.try_schema_ref(ref_) {
+                return self.inline_refs(&target, depth - 1);
+            }
+        }
+        let mut schema = schema.clone();
+        schema.definitions.clear();
+        for subschema in schema
+            .properties
+            .values_mut()
+            .chain(schema.pattern_properties.values_mut())
+            .chain(schema.items.iter_mut())
+            .chain(schema.all_of.iter_mut().flatten())
+            .chain(schema.any_of.iter_mut().flatten())
+            .chain(schema.one_of.iter_mut().flatten())
+            .chain(schema.not.as_deref_mut())
+        {
+            *subschema = self.inline_refs(subschema, depth);
+        }
+        schema
+    }
+
+    /// Generates `validate`, which checks all the constraints of a struct that
+    /// are not expressed by its type.
+    fn validate(
+        &self,
+        name: &syn::Ident,
+        generics: &Option<TokenStream>,
+        dependencies: &[(String, Option<Vec<String>>)],
+        optional: &[String],
+        validations: &[TokenStream],
+    ) -> TokenStream {
+        let validate = self.validate_path();
+        let checks = dependency_checks(dependencies, optional, self.options.identifier_case)
+            .map(|(condition, message)| {
+                quote! {
+                    if #condition {
+                        errors.push(#validate::ValidationError::new(#message));
+                    }
+                }
+            })
+            .chain(validations.iter().cloned())
+            .collect::<Vec<_>>();
+        let body = if checks.is_empty() {
+            quote!(Ok(()))
+        } else {
+            quote! {
+                let mut errors = Vec::new();
+                #(#checks)*
+                if errors.is_empty() {
+                    Ok(())
+                } else {
+                    Err(errors)
+                }
+            }
+        };
+        quote! {
+            impl #generics #name #generics {
+                /// Checks the constraints of the schema which are not expressed
+                /// by the type, including those of the nested types.
+                pub fn validate(&self) -> Result<(), Vec<#validate::ValidationError>> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// The checks of `validate` for the field of the property `field_name`
+    /// of type `typ`.
+    fn field_validation(
+        &self,
+        field_name: &str,
+        typ: &str,
+        schema: &Schema,
+    ) -> Option<TokenStream> {
+        let schema = self.schema(schema);
+        let validate = self.validate_path();
+        let (optional, typ) = match typ
+            .strip_prefix("Option<")
+            .and_then(|t| t.strip_suffix('>'))
+        {
+            Some(typ) => (true, typ),
+            None => (false, typ),
+        };
+        let typ = typ
+            .strip_prefix(&*self.recursion_pointer())
+            .and_then(|t| t.strip_prefix('<'))
+            .and_then(|t| t.strip_suffix('>'))
+            .unwrap_or(typ);
+        let bound = |bound: Option<u64>| match bound {
+            Some(bound) => quote!(Some(#bound)),
+            None => quote!(None),
+        };
+        let mut checks = Vec::new();
+        let mut nested = None;
+        match typ {
+            "String" | "&'a str" => {
+                if schema.min_length.is_some() || schema.max_length.is_some() {
+                    let min = bound(schema.min_length.as_ref().and_then(Value::as_u64));
+                    let max = bound(schema.max_length.map(|max| max as u64));
+                    checks.push(quote!(#validate::string_length(value, #min, #max)));
+                }
+                if let Some(pattern) = &schema.pattern {
+                    checks.push(quote!(#validate::pattern(value, #pattern)));
+                }
+            }
+            _ if matches!(typ, "i64" | "i128" | "u128" | "f64")
+                || typ.ends_with("OrderedFloat<f64>") =>
+            {
+                let number = match typ {
+                    "i64" | "i128" | "u128" => quote!(*value as f64),
+                    "f64" => quote!(*value),
+                    _ => quote!(value.0),
+                };
+                let (min, exclusive_min) =
+                    number_bound(schema.minimum, &schema.exclusive_minimum, |a, b| a >= b);
+                let (max, exclusive_max) =
+                    number_bound(schema.maximum, &schema.exclusive_maximum, |a, b| a <= b);
+                if min.is_some() || max.is_some() {
+                    let float = |bound: Option<f64>| match bound {
+                        Some(bound) => quote!(Some(#bound)),
+                        None => quote!(None),
+                    };
+                    let min = float(min);
+                    let max = float(max);
+                    checks.push(quote!(#validate::range(#number, #min, #max, #exclusive_min, #exclusive_max)));
+                }
+                if let Some(multiple) = schema.multiple_of {
+                    checks.push(quote!(#validate::multiple_of(#number, #multiple)));
+                }
+            }
+            _ if typ.starts_with("Vec<") || typ.starts_with("::std::collections::BTreeSet<") => {
+                if schema.min_items.is_some() || schema.max_items.is_some() {
+                    let min = bound(schema.min_items.as_ref().and_then(Value::as_u64));
+                    let max = bound(schema.max_items.map(|max| max as u64));
+                    checks.push(quote!(#validate::items_length(value.len(), #min, #max)));
+                }
+                let item = &typ[typ.find('<').unwrap() + 1..typ.len() - 1];
+                if self.validated.contains(without_lifetime(item)) {
+                    nested = Some(quote! {
+                        for (i, value) in value.iter().enumerate() {
+                            if let Err(nested) = value.validate() {
+                                let segment = format!("{}/{}", #field_name, i);
+                                errors.extend(nested.into_iter().map(|err| err.at(&segment)));
+                            }
+                        }
+                    });
+                }
+            }
+            _ if self.validated.contains(without_lifetime(typ)) => {
+                nested = Some(quote! {
+                    if let Err(nested) = value.validate() {
+                        errors.extend(nested.into_iter().map(|err| err.at(#field_name)));
+                    }
+                });
+            }
+            _ => (),
+        }
+        if let Some(not) = &schema.not {
+            let not = self.not_json(not);
+            checks.push(quote!(#validate::not(value, #not)));
+        }
+        if checks.is_empty() && nested.is_none() {
+            return None;
+        }
+        let checks = checks.iter().map(|check| {
+            quote! {
+                if let Err(err) = #check {
+                    errors.push(err.at(#field_name));
+                }
+            }
+        });
+        let field = field_ident(field_name, self.options.identifier_case);
+        Some(if optional {
+            quote! {
+                if let Some(value) = &self.#field {
+                    #(#checks)*
+                    #nested
+                }
             }
-            return quote! {
-                pub type #name = #typ;
+        } else {
+            quote! {
+                {
+                    let value = &self.#field;
+                    #(#checks)*
+                    #nested
+                }
+            }
+        })
+    }
+
+    fn validate_path(&self) -> TokenStream {
+        format!("{}validate", self.schemafy_path)
+            .parse::<TokenStream>()
+            .unwrap()
+    }
+
+    /// Records the `examples` of the schema which do not fit the type generated
+    /// for it, which [`try_expand`](Self::try_expand) reports.
+    fn check_examples(&self, name: &str, schema: &Schema) {
+        if self.options.check_examples {
+            self.record_example_mismatches(name, schema);
+        }
+    }
+
+    fn record_example_mismatches(&self, name: &str, schema: &Schema) {
+        for example in &schema.examples {
+            if let Err(err) = self.check_example("", schema, example) {
+                let mismatch = format!(
+                    "Example `{}` of `{}` does not fit the generated type: {}",
+                    example, name, err
+                );
+                let mut mismatches = self.example_mismatches.borrow_mut();
+                // A type may be generated several times in a pass
+                if !mismatches.contains(&mismatch) {
+                    mismatches.push(mismatch);
+                }
+            }
+        }
+    }
+
+    /// Checks that `value` would deserialize into the type generated for
+    /// `schema`. This is a best-effort check, anything which is not
+    /// understood is accepted.
+    fn check_example(&self, path: &str, schema: &Schema, value: &Value) -> Result<(), String> {
+        let schema = self.schema(schema);
+        let at = if path.is_empty() { "/" } else { path };
+        let mismatch = |expected: &str| {
+            Err(format!(
+                "expected {} at `{}`, found `{}`",
+                expected, at, value
+            ))
+        };
+        if value.is_null()
+            && (schema.nullable == Some(true) || schema.type_.contains(&SimpleTypes::Null))
+        {
+            return Ok(());
+        }
+        if let Some(enum_) = schema.enum_.as_ref().filter(|enum_| !enum_.is_empty()) {
+            return if enum_.contains(value) {
+                Ok(())
+            } else {
+                mismatch("one of the enum values")
+            };
+        }
+        if let Some(one_of) = schema.one_of.as_ref().filter(|one_of| one_of.len() >= 2) {
+            return if one_of
+                .iter()
+                .any(|schema| self.check_example(path, schema, value).is_ok())
+            {
+                Ok(())
+            } else {
+                mismatch("a value matching one of the `oneOf` schemas")
             };
+        }
+        let types = schema
+            .type_
+            .iter()
+            .filter(|type_| **type_ != SimpleTypes::Null)
+            .collect::<Vec<_>>();
+        let type_ = match types[..] {
+            [type_] => type_,
+            [] if !schema.properties.is_empty() => &SimpleTypes::Object,
+            // Anything else is a `serde_json::Value` or an untagged enum
+            _ => return Ok(()),
         };
-        type_decl
+        match type_ {
+            SimpleTypes::String if !value.is_string() => mismatch("a string"),
+            SimpleTypes::Integer if integer_type(&schema) == "i64" && value.as_i64().is_none() => {
+                mismatch("an integer")
+            }
+            // Integers beyond `u64` are only available as `f64`s
+            SimpleTypes::Integer if !value.as_f64().is_some_and(|f| f.fract() == 0.0) => {
+                mismatch("an integer")
+            }
+            SimpleTypes::Number if !value.is_number() => mismatch("a number"),
+            SimpleTypes::Boolean if !value.is_boolean() => mismatch("a boolean"),
+            SimpleTypes::Null if !value.is_null() => mismatch("null"),
+            SimpleTypes::Array => match value.as_array() {
+                Some(values) if tuple_items(&schema).is_some() => {
+                    let items = tuple_items(&schema).unwrap();
+                    if values.len() != items.len() {
+                        return mismatch(&format!("an array of {} items", items.len()));
+                    }
+                    items
+                        .iter()
+                        .zip(values)
+                        .enumerate()
+                        .try_for_each(|(i, (items, value))| {
+                            self.check_example(&format!("{}/{}", path, i), items, value)
+                        })
+                }
+                Some(values) => match schema.items.first() {
+                    Some(items) => values.iter().enumerate().try_for_each(|(i, value)| {
+                        self.check_example(&format!("{}/{}", path, i), items, value)
+                    }),
+                    None => Ok(()),
+                },
+                None => mismatch("an array"),
+            },
+            SimpleTypes::Object => match value.as_object() {
+                Some(object) => {
+                    for required in schema.required.iter().flatten() {
+                        if !object.contains_key(required) {
+                            return Err(format!(
+                                "missing the required property `{}` at `{}`",
+                                required, at
+                            ));
+                        }
+                    }
+                    for (key, value) in object {
+                        if let Some(property) = schema.properties.get(key) {
+                            self.check_example(&format!("{}/{}", path, key), property, value)?;
+                        }
+                    }
+                    Ok(())
+                }
+                None => mismatch("an object"),
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Generates `SCHEMA`, the JSON of the schema `name` was generated from.
+    fn schema_const(
+        &self,
+        name: &syn::Ident,
+        generics: &Option<TokenStream>,
+        schema: &Schema,
+    ) -> Option<TokenStream> {
+        if !self.options.schema_constants {
+            return None;
+        }
+        let json = serde_json::to_string(&*self.schema(schema)).unwrap();
+        Some(quote! {
+            impl #generics #name #generics {
+                pub const SCHEMA: &'static str = #json;
+            }
+        })
+    }
+
+    fn bound_constants(
+        &self,
+        name: &syn::Ident,
+        generics: &Option<TokenStream>,
+        schema: &Schema,
+    ) -> Option<TokenStream> {
+        if !self.options.bound_constants {
+            return None;
+        }
+        let schema = self.schema(schema);
+        let mut constants = Vec::new();
+        if let Some(values) = schema.enum_.as_ref().filter(|e| !e.is_empty()) {
+            let strings = values.iter().filter_map(Value::as_str);
+            let integers = values.iter().filter_map(Value::as_i64);
+            constants.push(if values.iter().any(Value::is_string) {
+                quote!(pub const ALLOWED: &'static [&'static str] = &[#(#strings),*];)
+            } else {
+                quote!(pub const ALLOWED: &'static [i64] = &[#(#integers),*];)
+            });
+        } else if self.is_string_newtype(&schema) {
+            if let Some(min) = schema.min_length.as_ref().and_then(Value::as_u64) {
+                constants.push(quote!(pub const MIN_LENGTH: u64 = #min;));
+            }
+            if let Some(max) = schema.max_length {
+                let max = max as u64;
+                constants.push(quote!(pub const MAX_LENGTH: u64 = #max;));
+            }
+        }
+        if constants.is_empty() {
+            return None;
+        }
+        Some(quote! {
+            impl #generics #name #generics {
+                #(#constants)*
+            }
+        })
     }
 
     pub fn expand(&mut self, schema: &Schema) -> TokenStream {
-        match self.root_name {
-            Some(name) => {
-                let schema = self.expand_schema(name, schema);
-                self.types.push((name.to_string(), schema));
+        self.try_expand(schema)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`expand`](Self::expand) but returns an error instead of panicking
+    /// if some `examples` do not fit the generated types, with `check_examples`
+    /// or `example_fixtures`.
+    pub fn try_expand(&mut self, schema: &Schema) -> Result<TokenStream, SchemafyError> {
+        self.comments.clear();
+        let comments = &mut self.comments;
+        walk_schema("#".to_string(), schema, &mut |path, schema| {
+            if let Some(comment) = &schema.comment {
+                comments.push((path.to_string(), comment.clone()));
             }
-            None => self.expand_definitions(schema),
+        });
+
+        let mut known = self.borrowed.len() + self.validated.len();
+        loop {
+            self.report.take();
+            self.example_mismatches.borrow_mut().clear();
+            match self.root_name {
+                Some(name) => {
+                    let schema = self.expand_schema(name, schema);
+                    self.types.push((name.to_string(), schema));
+                }
+                None => self.expand_definitions(schema),
+            }
+            // Whether a type borrows or can be validated is only known after it
+            // is generated so the types which refer to it before that need
+            // another pass
+            if self.borrowed.len() + self.validated.len() == known {
+                break;
+            }
+            known = self.borrowed.len() + self.validated.len();
+            self.types.clear();
+        }
+
+        let mismatches = self.example_mismatches.take();
+        if !mismatches.is_empty() {
+            return Err(SchemafyError::ExampleMismatch(mismatches));
         }
 
         let types = self.types.iter().map(|t| &t.1);
+        let module_doc = module_doc(schema)
+            .filter(|_| self.options.module_doc)
+            .map(|doc| doc.parse::<TokenStream>().unwrap());
 
-        quote! {
-            #( #types )*
+        Ok(if self.options.output_module.is_empty() {
+            quote! {
+                #module_doc
+                #( #types )*
+            }
+        } else {
+            let module = syn::Ident::new(&self.options.output_module, Span::call_site());
+            quote! {
+                pub mod #module {
+                    #module_doc
+                    #[allow(unused_imports)]
+                    use super::*;
+                    #( #types )*
+                }
+            }
+        })
+    }
+
+    /// Like [`expand`](Self::expand) but returns the code of each type
+    /// separately, keyed by the type's name.
+    pub fn expand_types(&mut self, schema: &Schema) -> BTreeMap<String, TokenStream> {
+        self.try_expand_types(schema)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Like [`expand_types`](Self::expand_types) but returns an error instead
+    /// of panicking, see [`try_expand`](Self::try_expand).
+    pub fn try_expand_types(
+        &mut self,
+        schema: &Schema,
+    ) -> Result<BTreeMap<String, TokenStream>, SchemafyError> {
+        self.try_expand(schema)?;
+        let mut types = BTreeMap::<String, TokenStream>::new();
+        for (name, tokens) in &self.types {
+            types
+                .entry(self.type_name(name))
+                .or_default()
+                .extend(tokens.clone());
         }
+        Ok(types)
     }
 
     pub fn expand_root(&mut self) -> TokenStream {
         self.expand(self.root)
     }
+
+    /// Hashes the code of each type generated by the previous call to
+    /// [`expand`](Self::expand), keyed by the type's name.
+    pub fn type_hashes(&self) -> BTreeMap<String, u64> {
+        let mut hashes = BTreeMap::new();
+        for (name, tokens) in &self.types {
+            let hash = hashes.entry(name.clone()).or_insert(FNV_OFFSET_BASIS);
+            *hash = fnv1a(*hash, tokens.to_string().as_bytes());
+        }
+        hashes
+    }
+
+    /// The counts of the types and fields generated by the previous call to
+    /// [`expand`](Self::expand).
+    pub fn report(&self) -> GenerationReport {
+        self.report.get()
+    }
+
+    /// The `$comment`s found by the previous call to [`expand`](Self::expand)
+    /// as pairs of the JSON pointer of the commented subschema and the comment.
+    pub fn comments(&self) -> &[(String, String)] {
+        &self.comments
+    }
+
+    /// The draft which the `$schema` of the root schema declares, see
+    /// [`Draft::of`].
+    pub fn draft(&self) -> Option<Draft> {
+        Draft::of(self.root)
+    }
+}
+
+/// The addresses of the subschemas of `root` which have an `allOf`, see
+/// `Expander::merged`.
+fn all_ofs(root: &Schema) -> BTreeMap<*const Schema, Option<Rc<Schema>>> {
+    let mut all_ofs = BTreeMap::new();
+    walk_schema("#".to_string(), root, &mut |path, schema| {
+        // `walk_schema` deserializes `additionalProperties` into temporaries
+        if schema
+            .all_of
+            .as_ref()
+            .is_some_and(|all_of| !all_of.is_empty())
+            && !path.contains("/additionalProperties")
+        {
+            all_ofs.insert(schema as *const Schema, None);
+        }
+    });
+    all_ofs
+}
+
+/// Calls `f` with the schema and each of its subschemas, along with their
+/// JSON pointer starting from `path`.
+fn walk_schema(path: String, schema: &Schema, f: &mut dyn FnMut(&str, &Schema)) {
+    fn child(path: &str, keyword: &str, key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            path,
+            keyword,
+            key.replace('~', "~0").replace('/', "~1")
+        )
+    }
+
+    f(&path, schema);
+    if let Some(components) = &schema.components {
+        for (key, value) in &components.schemas {
+            walk_schema(child(&path, "components/schemas", key), value, f);
+        }
+    }
+    for (keyword, schemas) in &[
+        ("definitions", &schema.definitions),
+        ("properties", &schema.properties),
+        ("patternProperties", &schema.pattern_properties),
+    ] {
+        for (key, value) in schemas.iter() {
+            walk_schema(child(&path, keyword, key), value, f);
+        }
+    }
+    for (keyword, schemas) in &[
+        ("items", Some(&schema.items)),
+        ("allOf", schema.all_of.as_ref()),
+        ("anyOf", schema.any_of.as_ref()),
+        ("oneOf", schema.one_of.as_ref()),
+    ] {
+        for (i, value) in schemas
+            .iter()
+            .flat_map(|schemas| schemas.iter())
+            .enumerate()
+        {
+            walk_schema(child(&path, keyword, &i.to_string()), value, f);
+        }
+    }
+    if let Some(not) = &schema.not {
+        walk_schema(format!("{}/not", path), not, f);
+    }
+    if let Some(names) = &schema.property_names {
+        walk_schema(format!("{}/propertyNames", path), names, f);
+    }
+    if let Some(content) = &schema.content_schema {
+        walk_schema(format!("{}/contentSchema", path), content, f);
+    }
+    if let Some(additional) = schema
+        .additional_properties
+        .as_ref()
+        .filter(|value| value.is_object())
+        .and_then(|value| serde_json::from_value::<Schema>(value.clone()).ok())
+    {
+        walk_schema(format!("{}/additionalProperties", path), &additional, f);
+    }
 }
 
 #[cfg(test)]
@@ -830,6 +4010,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn escaped_pointer_refs() {
+        assert_eq!(unescape_pointer_segment("a~1b~0c"), "a/b~c");
+        assert_eq!(unescape_pointer_segment("a%20b%7E1"), "a b/");
+        assert_eq!(unescape_pointer_segment("100%"), "100%");
+
+        let schema: Schema = serde_json::from_str(
+            r#"{"definitions": {"a/b": {"type": "string"}, "c d": {"type": "integer"}}}"#,
+        )
+        .unwrap();
+        let expander = Expander::new(Some("Root"), "::schemafy_core::", &schema);
+        assert_eq!(expander.type_ref("#/definitions/a~1b"), "AB");
+        assert_eq!(
+            expander.schema_ref("#/definitions/a~1b").type_,
+            [SimpleTypes::String]
+        );
+        assert_eq!(
+            expander.schema_ref("#/definitions/c%20d").type_,
+            [SimpleTypes::Integer]
+        );
+    }
+
+    #[test]
+    fn additional_properties_refs() {
+        let schema: Schema = serde_json::from_str(
+            r#"{"definitions": {"map": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "properties": {"count": {"type": "integer"}},
+                    "additionalProperties": {"type": "string"}
+                }
+            }}}"#,
+        )
+        .unwrap();
+        let expander = Expander::new(Some("Root"), "::schemafy_core::", &schema);
+        let count = "#/definitions/map/additionalProperties/properties/count";
+        assert_eq!(expander.schema_ref(count).type_, [SimpleTypes::Integer]);
+        let nested = "#/definitions/map/additionalProperties/additionalProperties";
+        assert_eq!(expander.schema_ref(nested).type_, [SimpleTypes::String]);
+        // The subschemas are deserialized once
+        match (expander.schema_ref(count), expander.schema_ref(count)) {
+            (Resolved::Owned(first), Resolved::Owned(second)) => {
+                assert!(Rc::ptr_eq(&first, &second))
+            }
+            _ => panic!("Expected the subschemas of `additionalProperties` to be owned"),
+        }
+        assert!(expander
+            .try_schema_ref("#/definitions/map/additionalProperties/properties/missing")
+            .is_err());
+    }
+
+    #[test]
+    fn ref_modules() {
+        let schema: Schema = serde_json::from_str(
+            r#"{"properties": {"home": {"$ref": "common.json#/definitions/address"}}}"#,
+        )
+        .unwrap();
+        let options = Options {
+            ref_modules: vec![
+                ("common.json".to_string(), syn::parse_quote!(crate::common)),
+                (
+                    "http://example.com/v2.json".to_string(),
+                    syn::parse_quote!(v2),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+            ..Options::default()
+        };
+        let mut expander =
+            Expander::with_options(Some("Root"), "::schemafy_core::", &schema, &options);
+        assert_eq!(
+            expander.type_ref("common.json#/definitions/address"),
+            "crate::common::Address"
+        );
+        assert_eq!(expander.type_ref("common.json"), "crate::common::Common");
+        assert_eq!(
+            expander.type_ref("http://example.com/v2.json#/definitions/user_name"),
+            "v2::UserName"
+        );
+        assert_eq!(expander.type_ref("#/definitions/address"), "Address");
+        assert!(expander.check_refs(&schema).is_ok());
+
+        let tokens = expander.expand(&schema).to_string();
+        assert!(
+            tokens.contains("pub home : Option < crate :: common :: Address >"),
+            "{}",
+            tokens
+        );
+    }
+
     #[test]
     fn embedded_type_names() {
         use std::collections::HashSet;
@@ -851,4 +4123,26 @@ mod tests {
         assert!(types.contains("RootKM"));
         assert!(types.contains("RootTV"));
     }
+
+    #[test]
+    fn boolean_schemas() {
+        let schema = parse(
+            r#"{
+                "properties": { "any": true, "never": false },
+                "items": false,
+                "additionalProperties": false
+            }"#,
+        )
+        .unwrap();
+        let any = &schema.properties["any"];
+        assert_eq!(*any, parse("{}").unwrap());
+        assert_eq!(schema.items.len(), 1);
+        assert_eq!(schema.properties["never"], schema.items[0]);
+        assert_eq!(schema.items[0].not.as_deref(), Some(any));
+        // The keywords kept as JSON values keep their booleans
+        assert_eq!(schema.additional_properties, Some(Value::Bool(false)));
+        // A schema which is still not shaped like a schema reports the original error
+        let err = parse(r#"{"items": false, "type": 1}"#).unwrap_err();
+        assert!(err.to_string().contains("line 1 column"), "{}", err);
+    }
 }
@@ -0,0 +1,17 @@
+/// Counts of what was generated for a schema, returned by
+/// [`Generator::generate_schema_with_report`](crate::Generator::generate_schema_with_report)
+/// to track how much of a schema could be given Rust types.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct GenerationReport {
+    /// The structs, including the newtypes.
+    pub structs: usize,
+    /// The enums, of unit variants as well as of `oneOf` alternatives.
+    pub enums: usize,
+    /// The type aliases.
+    pub aliases: usize,
+    /// The fields of the structs and of the struct variants of the enums.
+    pub fields: usize,
+    /// The fields whose type is or holds a `serde_json::Value`, because the
+    /// schema could not be turned into a more precise type.
+    pub value_fields: usize,
+}
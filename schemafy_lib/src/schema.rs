@@ -1,7 +1,7 @@
+pub type SchemaArray = Vec<Schema>;
 pub type PositiveInteger = i64;
 pub type PositiveIntegerDefault0 = serde_json::Value;
-pub type SchemaArray = Vec<Schema>;
-#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[derive(Clone, PartialEq, Debug, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename = "simpleTypes")]
 pub enum SimpleTypes {
     #[serde(rename = "array")]
@@ -19,100 +19,179 @@ pub enum SimpleTypes {
     #[serde(rename = "string")]
     String,
 }
+pub type ExclusiveBoundBool = bool;
+pub type ExclusiveBoundNumber = f64;
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ExclusiveBound {
+    Bool(ExclusiveBoundBool),
+    Number(ExclusiveBoundNumber),
+}
 pub type StringArray = Vec<String>;
+#[doc = " The reusable objects of an OpenAPI document."]
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+#[serde(rename = "components")]
+pub struct Components {
+    #[serde(default)]
+    pub schemas: ::schemafy_core::IndexMap<String, Schema>,
+}
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[doc = " If `exclusiveMaximum` is present then `maximum` must be present as well."]
+#[doc = " If `exclusiveMinimum` is present then `minimum` must be present as well."]
 pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "$ref")]
     pub ref_: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "$schema")]
-    pub schema: Option<String>,
+    #[serde(rename = "$comment")]
+    pub comment: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "additionalItems")]
-    pub additional_items: Option<serde_json::Value>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "additionalProperties")]
-    pub additional_properties: Option<serde_json::Value>,
+    #[serde(rename = "$recursiveRef")]
+    pub recursive_ref: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "allOf")]
-    pub all_of: Option<SchemaArray>,
+    #[serde(rename = "$dynamicRef")]
+    pub dynamic_ref: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "anyOf")]
-    pub any_of: Option<SchemaArray>,
+    pub id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub default: Option<serde_json::Value>,
-    #[serde(default)]
-    pub definitions: ::std::collections::BTreeMap<String, Schema>,
+    #[serde(rename = "$schema")]
+    pub schema: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dependencies: Option<::std::collections::BTreeMap<String, serde_json::Value>>,
+    pub title: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "enum")]
-    pub enum_: Option<Vec<serde_json::Value>>,
+    pub default: Option<serde_json::Value>,
+    #[serde(default)]
+    pub examples: Vec<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "enumNames")]
-    pub enum_names: Option<StringArray>,
+    #[serde(rename = "multipleOf")]
+    pub multiple_of: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "exclusiveMaximum")]
-    pub exclusive_maximum: Option<bool>,
+    pub exclusive_maximum: Option<ExclusiveBound>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "exclusiveMinimum")]
-    pub exclusive_minimum: Option<bool>,
+    pub exclusive_minimum: Option<ExclusiveBound>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub format: Option<String>,
+    #[serde(rename = "maxLength")]
+    pub max_length: Option<PositiveInteger>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub id: Option<String>,
+    #[serde(rename = "minLength")]
+    pub min_length: Option<PositiveIntegerDefault0>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "additionalItems")]
+    pub additional_items: Option<serde_json::Value>,
     #[serde(default)]
     #[serde(with = "::schemafy_core::one_or_many")]
     pub items: Vec<Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "prefixItems")]
+    pub prefix_items: Option<SchemaArray>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "maxItems")]
     pub max_items: Option<PositiveInteger>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "maxLength")]
-    pub max_length: Option<PositiveInteger>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "maxProperties")]
-    pub max_properties: Option<PositiveInteger>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub maximum: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "minItems")]
     pub min_items: Option<PositiveIntegerDefault0>,
+    #[serde(default = "Schema::default_unique_items")]
+    #[serde(rename = "uniqueItems")]
+    pub unique_items: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "minLength")]
-    pub min_length: Option<PositiveIntegerDefault0>,
+    #[serde(rename = "maxProperties")]
+    pub max_properties: Option<PositiveInteger>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "minProperties")]
     pub min_properties: Option<PositiveIntegerDefault0>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub minimum: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "multipleOf")]
-    pub multiple_of: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub not: Option<Box<Schema>>,
+    pub required: Option<StringArray>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "oneOf")]
-    pub one_of: Option<SchemaArray>,
+    #[serde(rename = "additionalProperties")]
+    pub additional_properties: Option<serde_json::Value>,
+    #[serde(default)]
+    pub definitions: ::schemafy_core::IndexMap<String, Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub pattern: Option<String>,
+    pub components: Option<Components>,
     #[serde(default)]
-    #[serde(rename = "patternProperties")]
-    pub pattern_properties: ::std::collections::BTreeMap<String, Schema>,
+    pub properties: ::schemafy_core::IndexMap<String, Schema>,
     #[serde(default)]
-    pub properties: ::std::collections::BTreeMap<String, Schema>,
+    #[serde(rename = "patternProperties")]
+    pub pattern_properties: ::schemafy_core::IndexMap<String, Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub required: Option<StringArray>,
+    #[serde(rename = "propertyNames")]
+    pub property_names: Option<Box<Schema>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub title: Option<String>,
+    pub dependencies: Option<::schemafy_core::IndexMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "dependentRequired")]
+    pub dependent_required: Option<::schemafy_core::IndexMap<String, StringArray>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "enum")]
+    pub enum_: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "const")]
+    pub const_: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "enumNames")]
+    pub enum_names: Option<StringArray>,
     #[serde(default)]
     #[serde(with = "::schemafy_core::one_or_many")]
     #[serde(rename = "type")]
     pub type_: Vec<SimpleTypes>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "uniqueItems")]
-    pub unique_items: Option<bool>,
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "contentMediaType")]
+    pub content_media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "contentSchema")]
+    pub content_schema: Option<Box<Schema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "allOf")]
+    pub all_of: Option<SchemaArray>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "anyOf")]
+    pub any_of: Option<SchemaArray>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "oneOf")]
+    pub one_of: Option<SchemaArray>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not: Option<Box<Schema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "readOnly")]
+    pub read_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "writeOnly")]
+    pub write_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "x-extends")]
+    pub x_extends: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "x-rust-optional")]
+    pub x_rust_optional: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "x-rust-skip")]
+    pub x_rust_skip: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "x-rust-no-default")]
+    pub x_rust_no_default: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "x-enum-varnames")]
+    pub x_enum_varnames: Option<StringArray>,
+    #[serde(default)]
+    #[serde(rename = "x-enum-descriptions")]
+    pub x_enum_descriptions: Vec<String>,
+}
+impl Schema {
+    fn default_unique_items() -> bool {
+        false
+    }
 }
@@ -0,0 +1,47 @@
+use std::fmt;
+
+use proc_macro2::TokenStream;
+
+use crate::Schema;
+
+/// Hooks into the expansion of a schema to add attributes of its own to the
+/// generated code, registered with
+/// [`GeneratorBuilder::with_visitor`](crate::GeneratorBuilder::with_visitor).
+///
+/// The methods do nothing unless they are implemented.
+pub trait SchemaVisitor: Sync {
+    /// Called for each generated struct with its name and the schema it is
+    /// generated from. The attributes pushed to `attributes`, such as
+    /// `#[non_exhaustive]`, are added to the struct after its derives.
+    #[allow(unused_variables)]
+    fn on_struct(&self, name: &str, schema: &Schema, attributes: &mut Vec<TokenStream>) {}
+
+    /// Called for each field of a generated struct with the name of the
+    /// struct, the name of the property and the schema of the property. The
+    /// attributes pushed to `attributes` are added to the field.
+    #[allow(unused_variables)]
+    fn on_field(
+        &self,
+        struct_name: &str,
+        property: &str,
+        schema: &Schema,
+        attributes: &mut Vec<TokenStream>,
+    ) {
+    }
+}
+
+impl fmt::Debug for dyn SchemaVisitor + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SchemaVisitor")
+    }
+}
+
+/// Visitors are only equal if they are the same value.
+impl PartialEq for dyn SchemaVisitor + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            self as *const Self as *const u8,
+            other as *const Self as *const u8,
+        )
+    }
+}
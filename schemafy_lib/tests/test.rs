@@ -40,3 +40,987 @@ fn test_str_to_ident() {
         Ident::new("thieves_tools", Span::call_site())
     );
 }
+
+#[test]
+fn deterministic_output() {
+    use schemafy_lib::Generator;
+
+    for input_file in &["src/schema.json", "tests/multiple-property-types.json"] {
+        let generate = || {
+            Generator::builder()
+                .with_root_name_str("Root")
+                .with_input_file(input_file)
+                .build()
+                .generate()
+                .to_string()
+        };
+        assert_eq!(generate(), generate(), "{}", input_file);
+    }
+}
+
+#[test]
+fn generate_parsed_schema() {
+    use schemafy_lib::Generator;
+
+    let json = std::fs::read_to_string("src/schema.json").expect("Read schema JSON file");
+    let schema = schemafy_lib::parse(&json).unwrap();
+    assert_eq!(schema.definitions.len(), 7);
+
+    let generator = Generator::builder()
+        .with_root_name_str("Schema")
+        .with_input_file("src/schema.json")
+        .build();
+    assert_eq!(
+        generator.generate_schema(&schema).to_string(),
+        generator.generate().to_string()
+    );
+
+    assert!(schemafy_lib::parse("{").is_err());
+}
+
+#[test]
+fn generate_schema_into() {
+    let schema = schemafy_lib::parse(r#"{"properties": {"a": {"type": "string"}}}"#).unwrap();
+    let generator = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .build();
+    let mut tokens = quote::quote! { use std::fmt; };
+    generator.generate_schema_into(&schema, &mut tokens);
+    assert_eq!(
+        tokens.to_string(),
+        format!("use std :: fmt ; {}", generator.generate_schema(&schema))
+    );
+}
+
+#[test]
+fn visitor() {
+    struct Visitor;
+    impl schemafy_lib::SchemaVisitor for Visitor {
+        fn on_struct(
+            &self,
+            _name: &str,
+            _schema: &schemafy_lib::Schema,
+            attributes: &mut Vec<proc_macro2::TokenStream>,
+        ) {
+            attributes.push(quote::quote! { #[non_exhaustive] });
+        }
+
+        fn on_field(
+            &self,
+            struct_name: &str,
+            property: &str,
+            schema: &schemafy_lib::Schema,
+            attributes: &mut Vec<proc_macro2::TokenStream>,
+        ) {
+            if struct_name == "Root" && property == "b" && schema.read_only == Some(true) {
+                attributes.push(quote::quote! { #[doc(hidden)] });
+            }
+        }
+    }
+
+    let schema = schemafy_lib::parse(
+        r#"{"properties": {"a": {"type": "string"}, "b": {"type": "string", "readOnly": true}}}"#,
+    )
+    .unwrap();
+    let tokens = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_visitor(&Visitor)
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.contains("# [non_exhaustive] pub struct Root"),
+        "{}",
+        tokens
+    );
+    assert!(
+        tokens.contains(
+            r#"# [serde (skip_serializing_if = "Option::is_none")] # [doc (hidden)] pub b"#
+        ),
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn titled_const_variants() {
+    let schema = schemafy_lib::parse(
+        r#"{"oneOf": [
+            {"const": "l", "title": "Light", "description": "Bright colors"},
+            {"const": "d", "title": "Very dark"}
+        ]}"#,
+    )
+    .unwrap();
+    let tokens = schemafy_lib::Generator::builder()
+        .with_root_name_str("Shade")
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.contains(
+            r#"# [doc = " Bright colors"] # [serde (rename = "l")] Light , # [serde (rename = "d")] VeryDark"#
+        ),
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn generation_report() {
+    let schema = schemafy_lib::parse(
+        r##"{
+            "properties": {
+                "name": {"$ref": "#/definitions/name"},
+                "color": {"enum": ["red", "green"]},
+                "inline": {"type": "object", "properties": {"any": {}}},
+                "extra": {"type": "array"}
+            },
+            "definitions": {
+                "name": {"type": "string"},
+                "shade": {"enum": ["light", "dark"]}
+            }
+        }"##,
+    )
+    .unwrap();
+    let generator = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .build();
+    let (tokens, report) = generator.generate_schema_with_report(&schema);
+    assert_eq!(
+        tokens.to_string(),
+        generator.generate_schema(&schema).to_string()
+    );
+    assert_eq!(
+        report,
+        schemafy_lib::GenerationReport {
+            structs: 2,
+            enums: 1,
+            aliases: 1,
+            fields: 5,
+            value_fields: 3,
+        },
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn no_default() {
+    let schema = schemafy_lib::parse(
+        r##"{
+            "type": "object",
+            "properties": {
+                "owner": {"$ref": "#/definitions/owner"},
+                "settings": {"$ref": "#/definitions/settings"}
+            },
+            "definitions": {
+                "owner": {
+                    "type": "object",
+                    "x-rust-no-default": true,
+                    "properties": {"name": {"type": "string"}}
+                },
+                "settings": {
+                    "type": "object",
+                    "properties": {"theme": {"type": "string"}}
+                }
+            }
+        }"##,
+    )
+    .unwrap();
+    let tokens = schemafy_lib::Generator::builder()
+        .with_root_name_str("Account")
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.contains(
+            "# [derive (Clone , PartialEq , Debug , Deserialize , Serialize)] # [serde (rename = \"owner\")] pub struct Owner"
+        ),
+        "{}",
+        tokens
+    );
+    assert!(
+        tokens.contains(
+            "# [derive (Clone , PartialEq , Debug , Default , Deserialize , Serialize)] # [serde (rename = \"settings\")] pub struct Settings"
+        ),
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn any_of_untyped_array() {
+    let json = r#"{
+        "type": "object",
+        "properties": {
+            "value": { "anyOf": [{ "type": "string" }, { "type": "array" }] }
+        }
+    }"#;
+    let schema = schemafy_lib::parse(json).unwrap();
+    let tokens = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.contains("pub value : Option < serde_json :: Value >"),
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn generate_to_dir() {
+    let output_dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("generate_to_dir");
+    let _ = std::fs::remove_dir_all(&output_dir);
+    schemafy_lib::Generator::builder()
+        .with_root_name_str("Schema")
+        .with_input_file("src/schema.json")
+        .build()
+        .generate_to_dir(&output_dir)
+        .unwrap();
+
+    let mod_rs = std::fs::read_to_string(output_dir.join("mod.rs")).unwrap();
+    for module in ["schema", "simple_types", "string_array"] {
+        assert!(
+            mod_rs.contains(&format!("mod {0};\npub use self::{0}::*;", module)),
+            "{}",
+            mod_rs
+        );
+    }
+    let schema = std::fs::read_to_string(output_dir.join("schema.rs")).unwrap();
+    assert!(schema.contains("use super::*;"), "{}", schema);
+    assert!(schema.contains("pub struct Schema"), "{}", schema);
+    assert!(!schema.contains("pub enum SimpleTypes"), "{}", schema);
+}
+
+#[test]
+fn module_doc() {
+    let schema = schemafy_lib::parse(
+        r#"{
+            "title": "Inventory",
+            "description": "The items in stock.\nCounted daily.",
+            "type": "object",
+            "properties": {"count": {"type": "integer"}}
+        }"#,
+    )
+    .unwrap();
+    let builder = || schemafy_lib::Generator::builder().with_root_name_str("Inventory");
+    let tokens = builder().build().generate_schema(&schema).to_string();
+    assert!(!tokens.contains("# !"), "{}", tokens);
+
+    let tokens = builder()
+        .with_module_doc(true)
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.starts_with(
+            r#"# ! [doc = " Inventory"] # ! [doc = ""] # ! [doc = " The items in stock."] # ! [doc = " Counted daily."]"#
+        ),
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn rustfmt_args() {
+    let output_file = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("rustfmt_args.rs");
+    schemafy_lib::Generator::builder()
+        .with_root_name_str("Schema")
+        .with_input_file("src/schema.json")
+        .with_rustfmt_args(["--edition", "2021", "--config", "hard_tabs=true"])
+        .build()
+        .generate_to_file(&output_file)
+        .unwrap();
+    let output = std::fs::read_to_string(&output_file).unwrap();
+    assert!(
+        output.contains("\n\tpub ref_: Option<String>,"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn formatter() {
+    let output_file = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("formatter.rs");
+    let generate = |formatter| {
+        schemafy_lib::Generator::builder()
+            .with_root_name_str("Schema")
+            .with_input_file("src/schema.json")
+            .with_formatter(formatter)
+            .build()
+            .generate_to_file(&output_file)
+            .map(|()| std::fs::read_to_string(&output_file).unwrap())
+    };
+
+    let output = generate(schemafy_lib::Formatter::None).unwrap();
+    assert!(!output.contains('\n'), "{}", output);
+    assert!(output.contains("pub struct Schema"), "{}", output);
+
+    #[cfg(feature = "prettyplease")]
+    {
+        let output = generate(schemafy_lib::Formatter::Prettyplease).unwrap();
+        assert!(
+            output.contains("\n    pub ref_: Option<String>,"),
+            "{}",
+            output
+        );
+    }
+}
+
+#[test]
+fn structured_errors() {
+    use schemafy_lib::{Generator, SchemafyError};
+
+    match schemafy_lib::parse("{") {
+        Err(SchemafyError::Parse(_)) => (),
+        result => panic!("{:?}", result),
+    }
+
+    let schema =
+        schemafy_lib::parse(r##"{"properties": {"a": {"$ref": "#/definitions/missing"}}}"##)
+            .unwrap();
+    let generator = Generator::builder().with_root_name_str("Root").build();
+    match generator.try_generate_schema(&schema) {
+        Err(SchemafyError::UnresolvedRef(ref_)) => assert_eq!(ref_, "#/definitions/missing"),
+        result => panic!("{:?}", result),
+    }
+
+    let schema = schemafy_lib::parse(
+        r##"{
+            "properties": { "a": { "$ref": "#/definitions/b/properties/missing" } },
+            "definitions": { "b": { "properties": { "c": { "type": "string" } } } }
+        }"##,
+    )
+    .unwrap();
+    match generator.try_generate_schema(&schema) {
+        Err(SchemafyError::UnresolvedRef(ref_)) => {
+            assert_eq!(ref_, "#/definitions/b/properties/missing")
+        }
+        result => panic!("{:?}", result),
+    }
+
+    let schema = schemafy_lib::parse(
+        r##"{
+            "properties": {
+                "a": { "allOf": [{ "type": "object" }, { "$ref": "#/definitions/b" }] },
+                "c": { "allOf": [{ "type": "number" }, { "type": ["integer", "null"] }] }
+            },
+            "definitions": { "b": { "type": ["string", "null"] } }
+        }"##,
+    )
+    .unwrap();
+    match generator.try_generate_schema(&schema) {
+        Err(SchemafyError::Unsupported(message)) => assert_eq!(
+            message,
+            "The `allOf` at `#/properties/a` can not be satisfied, \
+             its parts have the types `object` and `string` or `null`"
+        ),
+        result => panic!("{:?}", result),
+    }
+
+    let schema = schemafy_lib::parse(
+        r##"{
+            "properties": { "a": { "$ref": "#/definitions/b" } },
+            "definitions": { "b": { "x-rust-skip": true, "type": "string" } }
+        }"##,
+    )
+    .unwrap();
+    match generator.try_generate_schema(&schema) {
+        Err(SchemafyError::Unsupported(message)) => assert_eq!(
+            message,
+            "`#/properties/a` refers to `#/definitions/b`, which is skipped by `x-rust-skip`"
+        ),
+        result => panic!("{:?}", result),
+    }
+
+    match Generator::builder()
+        .with_input_file("missing.json")
+        .build()
+        .try_generate()
+    {
+        Err(SchemafyError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+        result => panic!("{:?}", result),
+    }
+
+    let output_file = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("rustfmt_error.rs");
+    match Generator::builder()
+        .with_root_name_str("Schema")
+        .with_input_file("src/schema.json")
+        .with_rustfmt_args(["--edition", "1999"])
+        .build()
+        .generate_to_file(&output_file)
+    {
+        Err(SchemafyError::Rustfmt(_)) => (),
+        result => panic!("{:?}", result),
+    }
+}
+
+#[test]
+fn generate_value() {
+    let json = std::fs::read_to_string("src/schema.json").expect("Read schema JSON file");
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        schemafy_lib::parse_value(&value).unwrap(),
+        schemafy_lib::parse(&json).unwrap()
+    );
+
+    value["definitions"]["extra"] = serde_json::json!({ "type": "string" });
+    let code = schemafy_lib::generate_value(Some("Schema"), &value).unwrap();
+    assert!(code.contains("pub type Extra = String"), "{}", code);
+    assert!(code.contains("pub struct Schema"), "{}", code);
+
+    assert!(schemafy_lib::parse_value(&serde_json::json!({ "type": 1 })).is_err());
+}
+
+#[test]
+fn doc_links() {
+    let json = r##"{
+        "type": "object",
+        "properties": {
+            "head": { "$ref": "#/definitions/node", "description": "The first node" },
+            "nodes": { "type": "array", "items": { "$ref": "#/definitions/node" } },
+            "name": { "type": "string" }
+        },
+        "definitions": {
+            "node": { "type": "object", "properties": { "value": { "type": "integer" } } }
+        }
+    }"##;
+    let schema = schemafy_lib::parse(json).unwrap();
+    let tokens = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_doc_links(true)
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.contains(
+            r#"# [doc = " The first node"] # [doc = ""] # [doc = " See also [`Node`]."]"#
+        ),
+        "{}",
+        tokens
+    );
+    assert_eq!(tokens.matches("See also").count(), 2, "{}", tokens);
+}
+
+#[test]
+#[should_panic(expected = "The `oneOf` of `RootValue` is not an adjacently tagged envelope")]
+fn forced_adjacent_tagging() {
+    let json = r#"{
+        "type": "object",
+        "properties": {
+            "value": {
+                "oneOf": [{ "id": "Text", "type": "string" }, { "id": "Count", "type": "integer" }]
+            }
+        }
+    }"#;
+    let schema = schemafy_lib::parse(json).unwrap();
+    schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_one_of_tagging(schemafy_lib::OneOfTagging::Adjacent)
+        .build()
+        .generate_schema(&schema);
+}
+
+#[test]
+fn forced_adjacent_tagging_error() {
+    let json = r#"{
+        "type": "object",
+        "properties": {
+            "value": {
+                "oneOf": [{ "id": "Text", "type": "string" }, { "id": "Count", "type": "integer" }]
+            }
+        }
+    }"#;
+    let schema = schemafy_lib::parse(json).unwrap();
+    let err = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_one_of_tagging(schemafy_lib::OneOfTagging::Adjacent)
+        .build()
+        .try_generate_schema(&schema)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The `oneOf` at `#/properties/value` is not an adjacently tagged envelope"
+    );
+    assert!(matches!(err, schemafy_lib::SchemafyError::Unsupported(_)));
+
+    let json = std::fs::read_to_string("../tests/adjacently-tagged.json").unwrap();
+    let schema = schemafy_lib::parse(&json).unwrap();
+    assert!(schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_one_of_tagging(schemafy_lib::OneOfTagging::Adjacent)
+        .build()
+        .try_generate_schema(&schema)
+        .is_ok());
+}
+
+#[test]
+fn analyze() {
+    let json = r##"{
+        "type": "object",
+        "properties": {
+            "updated": {
+                "if": { "type": "string" },
+                "then": { "format": "date-time" }
+            },
+            "tags": {
+                "anyOf": [
+                    { "type": "string" },
+                    { "type": "array", "items": { "type": "string" } }
+                ]
+            },
+            "id": { "type": ["string", "integer"], "x-internal": true },
+            "pair": { "type": "array", "items": [{ "type": "string" }, { "type": "integer" }] }
+        },
+        "patternProperties": { "^x-": {} },
+        "definitions": {
+            "any": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+            "not_empty": { "type": "string", "not": { "maxLength": 0 } }
+        }
+    }"##;
+
+    let unsupported = schemafy_lib::analyze(json)
+        .unwrap()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        unsupported,
+        [
+            "`#`: `patternProperties` is ignored",
+            "`#/definitions/any`: `anyOf` becomes `serde_json::Value`",
+            "`#/definitions/not_empty`: `not` is ignored unless `validation` is set",
+            "`#/properties/id`: `type` becomes `serde_json::Value` unless \
+             `untagged_type_unions` is set",
+            "`#/properties/pair`: `items` is a tuple of which only the first schema is used",
+            "`#/properties/updated`: `if` is not supported and is ignored",
+            "`#/properties/updated`: `then` is not supported and is ignored",
+        ]
+    );
+}
+
+#[test]
+fn draft() {
+    use schemafy_lib::{Draft, Expander};
+
+    for (uri, draft) in &[
+        (
+            "http://json-schema.org/draft-04/schema#",
+            Some(Draft::Draft04),
+        ),
+        (
+            "http://json-schema.org/draft-06/schema",
+            Some(Draft::Draft06),
+        ),
+        (
+            "https://json-schema.org/draft-07/schema#",
+            Some(Draft::Draft07),
+        ),
+        (
+            "https://json-schema.org/draft/2019-09/schema",
+            Some(Draft::Draft201909),
+        ),
+        (
+            "https://json-schema.org/draft/2020-12/schema",
+            Some(Draft::Draft202012),
+        ),
+        ("https://example.com/schema", None),
+    ] {
+        assert_eq!(Draft::from_uri(uri), *draft, "{}", uri);
+    }
+
+    let schema =
+        schemafy_lib::parse(r#"{"$schema": "https://json-schema.org/draft/2020-12/schema"}"#)
+            .unwrap();
+    let expander = Expander::new(None, "::schemafy_core::", &schema);
+    assert_eq!(expander.draft(), Some(Draft::Draft202012));
+    assert!(expander.draft() > Some(Draft::Draft07));
+    assert_eq!(Draft::of(&schemafy_lib::parse("{}").unwrap()), None);
+}
+
+#[test]
+fn sort_enum_variants() {
+    let json = r##"{
+        "definitions": {
+            "color": { "enum": ["red", "green", "blue"] },
+            "level": { "enum": [3, 1, 2], "enumNames": ["high", "low", "medium"] },
+            "value": {
+                "oneOf": [{ "id": "Text", "type": "string" }, { "id": "Count", "type": "integer" }]
+            }
+        }
+    }"##;
+    let schema = schemafy_lib::parse(json).unwrap();
+    let generate = |sort| {
+        schemafy_lib::Generator::builder()
+            .with_sort_enum_variants(sort)
+            .build()
+            .generate_schema(&schema)
+            .to_string()
+    };
+    let sorted = generate(true);
+    assert!(
+        sorted.contains(
+            r#"pub enum Color { # [serde (rename = "blue")] Blue , # [serde (rename = "green")] Green , # [serde (rename = "red")] Red }"#
+        ),
+        "{}",
+        sorted
+    );
+    assert!(
+        sorted.contains("pub enum Level { High = 3 , Low = 1 , Medium = 2 }"),
+        "{}",
+        sorted
+    );
+    // The variants of untagged enums are tried in order
+    assert!(sorted.find("Text (") < sorted.find("Count ("), "{}", sorted);
+    let unsorted = generate(false);
+    assert!(
+        unsorted.contains(r#"Red , # [serde (rename = "green")] Green"#),
+        "{}",
+        unsorted
+    );
+}
+
+#[test]
+fn screaming_snake_enums() {
+    let json = r##"{
+        "definitions": {
+            "priority": { "enum": ["LOW", "VERY_HIGH", null] },
+            "mixed": { "enum": ["LOW", "medium"] },
+            "digits": { "enum": ["HTTP_2"] }
+        }
+    }"##;
+    let schema = schemafy_lib::parse(json).unwrap();
+    let tokens = schemafy_lib::Generator::builder()
+        .build()
+        .generate_schema(&schema)
+        .to_string();
+    assert!(
+        tokens.contains(
+            r#"# [serde (rename_all = "SCREAMING_SNAKE_CASE")] pub enum Priority_ { Low , VeryHigh }"#
+        ),
+        "{}",
+        tokens
+    );
+    assert!(
+        tokens.contains(
+            r#"pub enum Mixed { # [serde (rename = "LOW")] Low , # [serde (rename = "medium")] Medium }"#
+        ),
+        "{}",
+        tokens
+    );
+    assert!(
+        tokens.contains(r#"pub enum Digits { # [serde (rename = "HTTP_2")] Http2 }"#),
+        "{}",
+        tokens
+    );
+}
+
+#[test]
+fn expand_conversions() {
+    let v1 = r##"{
+        "type": "object",
+        "properties": {
+            "authorName": { "type": "string" },
+            "status": { "$ref": "#/definitions/status" },
+            "reviewers": { "type": "array", "items": { "$ref": "#/definitions/strict" } }
+        },
+        "required": ["authorName"],
+        "definitions": {
+            "status": { "enum": ["draft", "published"] },
+            "strict": { "type": "object", "properties": { "a": { "type": "string" } } }
+        }
+    }"##;
+    let v2 = r##"{
+        "type": "object",
+        "properties": {
+            "author_name": { "type": "string" },
+            "subtitle": { "type": "string" },
+            "status": { "$ref": "#/definitions/status" }
+        },
+        "required": ["author_name"],
+        "definitions": {
+            "status": { "enum": ["draft", "published", "archived"] },
+            "strict": {
+                "type": "object",
+                "properties": { "a": { "type": "string" }, "b": { "type": "string" } },
+                "required": ["b"]
+            }
+        }
+    }"##;
+    let v1 = schemafy_lib::parse(v1).unwrap();
+    let v2 = schemafy_lib::parse(v2).unwrap();
+    let expand = |matching| {
+        schemafy_lib::expand_conversions(Some("Doc"), "v1::", &v1, "v2::", &v2, matching)
+            .to_string()
+    };
+
+    let tokens = expand(schemafy_lib::FieldMatching::FieldName);
+    assert!(
+        tokens.contains(
+            "impl From < v1 :: Doc > for v2 :: Doc { fn from (value : v1 :: Doc) -> Self { \
+             v2 :: Doc { author_name : value . author_name , status : value . status . map (| v | v . into ()) , \
+             subtitle : Default :: default () } } }"
+        ),
+        "{}",
+        tokens
+    );
+    assert!(
+        tokens.contains(
+            "match value { v1 :: Status :: Draft => v2 :: Status :: Draft , \
+             v1 :: Status :: Published => v2 :: Status :: Published }"
+        ),
+        "{}",
+        tokens
+    );
+    // `b` is required but does not exist in the old version
+    assert!(!tokens.contains("v1 :: Strict"), "{}", tokens);
+
+    // `authorName` and `author_name` are different properties
+    let tokens = expand(schemafy_lib::FieldMatching::PropertyName);
+    assert!(!tokens.contains("v1 :: Doc"), "{}", tokens);
+}
+
+#[test]
+fn type_hashes() {
+    use schemafy_lib::{changed_types, Generator};
+
+    let generator = Generator::builder().with_root_name_str("Root").build();
+    let before = schemafy_lib::parse(
+        r##"{
+            "properties": { "a": { "$ref": "#/definitions/a" } },
+            "definitions": {
+                "a": { "type": "string" },
+                "b": { "type": "integer" },
+                "c": { "type": "boolean" }
+            }
+        }"##,
+    )
+    .unwrap();
+    let after = schemafy_lib::parse(
+        r##"{
+            "properties": { "a": { "$ref": "#/definitions/a" } },
+            "definitions": {
+                "a": { "type": "string" },
+                "b": { "type": "number" },
+                "d": { "type": "boolean" }
+            }
+        }"##,
+    )
+    .unwrap();
+
+    let before = generator.type_hashes(&before);
+    assert_eq!(
+        before.keys().collect::<Vec<_>>(),
+        vec!["Root", "a", "b", "c"]
+    );
+    assert_eq!(changed_types(&before, &before), Vec::<String>::new());
+    assert_eq!(
+        changed_types(&before, &generator.type_hashes(&after)),
+        vec!["b", "c", "d"]
+    );
+}
+
+#[test]
+fn comments() {
+    let schema = schemafy_lib::parse(
+        r##"{
+            "$comment": "root",
+            "properties": {
+                "a/b": { "$comment": "property", "type": "string" }
+            },
+            "definitions": {
+                "c": {
+                    "type": "array",
+                    "items": { "$comment": "item", "type": "integer" }
+                }
+            }
+        }"##,
+    )
+    .unwrap();
+
+    let (tokens, comments) = schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .build()
+        .generate_schema_with_comments(&schema);
+    assert!(!tokens.to_string().contains("property"));
+    assert_eq!(
+        comments,
+        vec![
+            ("#".to_string(), "root".to_string()),
+            ("#/definitions/c/items/0".to_string(), "item".to_string()),
+            ("#/properties/a~1b".to_string(), "property".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn preserve_order() {
+    let schema = schemafy_lib::parse(
+        r#"{
+            "type": "object",
+            "properties": {
+                "zebra": { "type": "string" },
+                "apple": { "type": "object", "additionalProperties": { "type": "integer" } },
+                "mango": { "type": "boolean" }
+            }
+        }"#,
+    )
+    .unwrap();
+    let generate = |preserve_order| {
+        schemafy_lib::Generator::builder()
+            .with_root_name_str("Root")
+            .with_preserve_order(preserve_order)
+            .build()
+            .generate_schema(&schema)
+            .to_string()
+    };
+    let field_order = |code: &str| {
+        let mut fields = ["zebra", "apple", "mango"];
+        fields.sort_by_key(|field| code.find(&format!("pub {} :", field)).unwrap());
+        fields
+    };
+
+    let sorted = generate(false);
+    assert_eq!(field_order(&sorted), ["apple", "mango", "zebra"]);
+    assert!(sorted.contains("BTreeMap < String , i64 >"));
+
+    let ordered = generate(true);
+    assert_eq!(field_order(&ordered), ["zebra", "apple", "mango"]);
+    assert!(ordered.contains(":: schemafy_core :: IndexMap < String , i64 >"));
+}
+
+const EXAMPLES: &str = r##"{
+    "type": "object",
+    "properties": {
+        "id": { "type": "integer", "examples": [1, 2] },
+        "tags": { "type": "array", "items": { "$ref": "#/definitions/tag" } }
+    },
+    "required": ["id"],
+    "definitions": {
+        "tag": { "enum": ["a", "b"] }
+    },
+    "examples": [{ "id": 1, "tags": ["a"] }, { "id": 2 }]
+}"##;
+
+fn check_examples(json: &str) {
+    let schema = schemafy_lib::parse(json).unwrap();
+    schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_check_examples(true)
+        .build()
+        .generate_schema(&schema);
+}
+
+#[test]
+fn check_examples_accepts_matching_examples() {
+    check_examples(EXAMPLES);
+}
+
+#[test]
+#[should_panic(
+    expected = "Example `{\"id\":1,\"tags\":[\"c\"]}` of `Root` does not fit \
+                           the generated type: expected one of the enum values at `/tags/0`"
+)]
+fn check_examples_rejects_enum_mismatch() {
+    check_examples(&EXAMPLES.replace(r#""tags": ["a"]"#, r#""tags": ["c"]"#));
+}
+
+#[test]
+#[should_panic(expected = "missing the required property `id` at `/`")]
+fn check_examples_rejects_missing_property() {
+    check_examples(&EXAMPLES.replace(r#"{ "id": 2 }"#, "{}"));
+}
+
+#[test]
+#[should_panic(expected = "Example `\"2\"` of `Root.id`")]
+fn check_examples_rejects_property_example() {
+    check_examples(&EXAMPLES.replace("[1, 2]", r#"[1, "2"]"#));
+}
+
+#[test]
+#[should_panic(expected = "Example `{}` of `Root` does not fit")]
+fn example_fixtures_reject_mismatch() {
+    let schema = schemafy_lib::parse(&EXAMPLES.replace(r#"{ "id": 2 }"#, "{}")).unwrap();
+    schemafy_lib::Generator::builder()
+        .with_root_name_str("Root")
+        .with_example_fixtures(true)
+        .build()
+        .generate_schema(&schema);
+}
+
+#[test]
+fn example_mismatch_error() {
+    let schema = schemafy_lib::parse(&EXAMPLES.replace(r#"{ "id": 2 }"#, "{}")).unwrap();
+    let builder = || schemafy_lib::Generator::builder().with_root_name_str("Root");
+    for generator in [
+        builder().with_check_examples(true).build(),
+        builder().with_example_fixtures(true).build(),
+    ] {
+        match generator.try_generate_schema(&schema) {
+            Err(schemafy_lib::SchemafyError::ExampleMismatch(mismatches)) => assert_eq!(
+                mismatches,
+                ["Example `{}` of `Root` does not fit the generated type: \
+                  missing the required property `id` at `/`"]
+            ),
+            result => panic!("{:?}", result),
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_input() {
+    use schemafy_lib::Generator;
+
+    let generate = |input_file| {
+        Generator::builder()
+            .with_root_name_str("Yaml")
+            .with_input_file(input_file)
+            .build()
+            .generate()
+            .to_string()
+    };
+    assert_eq!(generate("tests/yaml.yaml"), generate("tests/yaml.json"));
+}
+
+#[test]
+fn generate_many() {
+    use schemafy_lib::{Generator, SchemafyError};
+
+    let tokens = Generator::builder()
+        .build()
+        .generate_many(&[
+            "tests/bundle/pet.json",
+            "tests/bundle/owner.json",
+            "tests/bundle/common.json",
+        ])
+        .unwrap()
+        .to_string();
+    for expected in &[
+        "pub struct Pet { pub name : String",
+        "pub tag : Option < Tag >",
+        "pub struct Owner",
+        "pub address : Option < Address >",
+        "pub favorite_tag : Option < Tag >",
+        "pub pets : Vec < Pet >",
+        "pub struct Address",
+    ] {
+        assert!(tokens.contains(expected), "{}", tokens);
+    }
+    // The definition of both files is only generated once
+    assert_eq!(tokens.matches("pub type Tag =").count(), 1, "{}", tokens);
+    // A file of definitions has no type of its own
+    assert!(!tokens.contains("Common"), "{}", tokens);
+
+    let err = Generator::builder()
+        .build()
+        .generate_many(&["tests/bundle/pet.json", "tests/bundle/conflict.json"])
+        .unwrap_err();
+    assert!(
+        matches!(&err, SchemafyError::IdentifierCollision(name) if name == "tag"),
+        "{:?}",
+        err
+    );
+}
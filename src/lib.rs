@@ -54,6 +54,9 @@
 /// If the `root` parameter is supplied, then a type will be
 /// generated from the root of the schema.
 ///
+/// Other options of [`schemafy_lib::Generator`] can be set in the same way
+/// before the path, e.g. `string_length_validation: true`.
+///
 /// ```rust
 /// extern crate serde;
 /// extern crate schemafy_core;
@@ -77,33 +80,175 @@ pub fn schemafy(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let def = syn::parse_macro_input!(tokens as Def);
     let root_name = def.root;
     let input_file = def.input_file.value();
+    let input_span = def.input_file.span();
     schemafy_lib::Generator::builder()
         .with_root_name(root_name)
         .with_input_file(&input_file)
+        .with_options(def.options)
         .build()
-        .generate()
+        .try_generate()
+        .unwrap_or_else(|err| syn::Error::new(input_span, err).to_compile_error())
         .into()
 }
 
 struct Def {
     root: Option<String>,
+    options: schemafy_lib::Options<'static>,
     input_file: syn::LitStr,
 }
 
 impl syn::parse::Parse for Def {
     fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
-        let root = if input.peek(syn::Ident) {
-            let root_ident: syn::Ident = input.parse()?;
-            if root_ident != "root" {
-                return Err(syn::Error::new(root_ident.span(), "Expected `root`"));
-            }
+        let mut root = None;
+        let mut options = schemafy_lib::Options::default();
+        while input.peek(syn::Ident) {
+            let option: syn::Ident = input.parse()?;
             input.parse::<syn::Token![:]>()?;
-            Some(input.parse::<syn::Ident>()?.to_string())
-        } else {
-            None
-        };
+            if option == "root" {
+                root = Some(input.parse::<syn::Ident>()?.to_string());
+            } else if option == "string_length_validation" {
+                options.string_length_validation = input.parse::<syn::LitBool>()?.value;
+            } else if option == "untagged_type_unions" {
+                options.untagged_type_unions = input.parse::<syn::LitBool>()?.value;
+            } else if option == "schema_constants" {
+                options.schema_constants = input.parse::<syn::LitBool>()?.value;
+            } else if option == "type_prefix" {
+                options.type_prefix = input.parse::<syn::LitStr>()?.value();
+            } else if option == "type_suffix" {
+                options.type_suffix = input.parse::<syn::LitStr>()?.value();
+            } else if option == "recursion_pointer" {
+                options.recursion_pointer = input.parse::<syn::LitStr>()?.parse()?;
+            } else if option == "serde_json_path" {
+                options.serde_json_path = input.parse::<syn::LitStr>()?.value();
+            } else if option == "serde_path" {
+                options.serde_path = input.parse::<syn::LitStr>()?.value();
+            } else if option == "output_module" {
+                options.output_module = input.parse::<syn::LitStr>()?.value();
+            } else if option == "identifier_case" {
+                let case = input.parse::<syn::LitStr>()?;
+                options.identifier_case = match &*case.value() {
+                    "rust" => schemafy_lib::IdentifierCase::Rust,
+                    "camel" => schemafy_lib::IdentifierCase::Camel,
+                    "preserve" => schemafy_lib::IdentifierCase::Preserve,
+                    _ => {
+                        return Err(syn::Error::new(
+                            case.span(),
+                            format!(
+                                "Unknown `identifier_case` `{}`, expected `rust`, `camel` or `preserve`",
+                                case.value()
+                            ),
+                        ))
+                    }
+                };
+            } else if option == "ref_modules" {
+                let modules = input.parse::<syn::LitStr>()?;
+                for entry in modules
+                    .value()
+                    .split(',')
+                    .filter(|entry| !entry.trim().is_empty())
+                {
+                    let (document, module) = entry.split_once('=').ok_or_else(|| {
+                        syn::Error::new(
+                            modules.span(),
+                            format!("Expected `document=module`, found `{}`", entry.trim()),
+                        )
+                    })?;
+                    let module = syn::parse_str(module.trim()).map_err(|err| {
+                        syn::Error::new(
+                            modules.span(),
+                            format!("Invalid module `{}`: {}", module.trim(), err),
+                        )
+                    })?;
+                    options
+                        .ref_modules
+                        .insert(document.trim().to_string(), module);
+                }
+            } else if option == "base_derives" {
+                let derives = input.parse::<syn::LitStr>()?;
+                let derives = derives.parse_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )?;
+                options.base_derives = derives.into_iter().collect();
+            } else if option == "one_of_tagging" {
+                let tagging = input.parse::<syn::LitStr>()?;
+                options.one_of_tagging = match &*tagging.value() {
+                    "untagged" => schemafy_lib::OneOfTagging::Untagged,
+                    "auto" => schemafy_lib::OneOfTagging::Auto,
+                    "adjacent" => schemafy_lib::OneOfTagging::Adjacent,
+                    _ => {
+                        return Err(syn::Error::new(
+                            tagging.span(),
+                            format!(
+                                "Unknown `one_of_tagging` `{}`, expected `untagged`, `auto` or `adjacent`",
+                                tagging.value()
+                            ),
+                        ))
+                    }
+                };
+            } else if option == "serde_default_container" {
+                options.serde_default_container = input.parse::<syn::LitBool>()?.value;
+            } else if option == "dependency_validation" {
+                options.dependency_validation = input.parse::<syn::LitBool>()?.value;
+            } else if option == "ordered_floats" {
+                options.ordered_floats = input.parse::<syn::LitBool>()?.value;
+            } else if option == "check_examples" {
+                options.check_examples = input.parse::<syn::LitBool>()?.value;
+            } else if option == "preserve_order" {
+                options.preserve_order = input.parse::<syn::LitBool>()?.value;
+            } else if option == "borrow" {
+                options.borrow = input.parse::<syn::LitBool>()?.value;
+            } else if option == "validation" {
+                options.validation = input.parse::<syn::LitBool>()?.value;
+            } else if option == "unique_item_sets" {
+                options.unique_item_sets = input.parse::<syn::LitBool>()?.value;
+            } else if option == "redact_secrets" {
+                options.redact_secrets = input.parse::<syn::LitBool>()?.value;
+            } else if option == "bound_constants" {
+                options.bound_constants = input.parse::<syn::LitBool>()?.value;
+            } else if option == "doc_links" {
+                options.doc_links = input.parse::<syn::LitBool>()?.value;
+            } else if option == "redact_debug" {
+                options.redact_debug = input.parse::<syn::LitBool>()?.value;
+            } else if option == "map_size_validation" {
+                options.map_size_validation = input.parse::<syn::LitBool>()?.value;
+            } else if option == "sort_enum_variants" {
+                options.sort_enum_variants = input.parse::<syn::LitBool>()?.value;
+            } else if option == "json_from_str" {
+                options.json_from_str = input.parse::<syn::LitBool>()?.value;
+            } else if option == "module_doc" {
+                options.module_doc = input.parse::<syn::LitBool>()?.value;
+            } else if option == "eq_ignore_read_only" {
+                options.eq_ignore_read_only = input.parse::<syn::LitBool>()?.value;
+            } else if option == "string_formats" {
+                options.string_formats = input.parse::<syn::LitBool>()?.value;
+            } else if option == "skip_empty_collections" {
+                options.skip_empty_collections = input.parse::<syn::LitBool>()?.value;
+            } else if option == "untagged_fallbacks" {
+                options.untagged_fallbacks = input.parse::<syn::LitBool>()?.value;
+            } else if option == "merge_patch" {
+                options.merge_patch = input.parse::<syn::LitBool>()?.value;
+            } else if option == "string_integers" {
+                options.string_integers = input.parse::<syn::LitBool>()?.value;
+            } else if option == "typed_map_keys" {
+                options.typed_map_keys = input.parse::<syn::LitBool>()?.value;
+            } else if option == "box_variants" {
+                options.box_variants = input.parse::<syn::LitBool>()?.value;
+            } else if option == "example_fixtures" {
+                options.example_fixtures = input.parse::<syn::LitBool>()?.value;
+            } else if option == "str_eq" {
+                options.str_eq = input.parse::<syn::LitBool>()?.value;
+            } else if option == "unix_timestamps" {
+                options.unix_timestamps = input.parse::<syn::LitBool>()?.value;
+            } else {
+                return Err(syn::Error::new(
+                    option.span(),
+                    format!("Unknown option `{}`", option),
+                ));
+            }
+        }
         Ok(Def {
             root,
+            options,
             input_file: input.parse()?,
         })
     }
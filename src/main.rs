@@ -3,7 +3,7 @@ use std::path::Path;
 use std::process::{Command, Stdio};
 
 use anyhow::{anyhow, bail, Context, Result};
-use schemafy_lib::Generator;
+use schemafy_lib::{Formatter, Generator};
 use structopt::StructOpt;
 use tempfile::NamedTempFile;
 
@@ -16,18 +16,51 @@ struct Opts {
     /// Output file [default: stdout]
     #[structopt(short, long, value_name = "PATH")]
     output: Option<String>,
+    /// Argument for rustfmt, can be repeated [default: --edition 2018]
+    #[structopt(
+        long = "rustfmt-arg",
+        value_name = "ARG",
+        allow_hyphen_values = true,
+        number_of_values = 1
+    )]
+    rustfmt_args: Vec<String>,
+    /// Formatter of the output: rustfmt, prettyplease or none
+    #[structopt(long, value_name = "NAME", default_value = "rustfmt", parse(try_from_str = parse_formatter))]
+    formatter: Formatter,
     /// JSON schema file
     schema_path: String,
 }
 
+fn parse_formatter(name: &str) -> Result<Formatter> {
+    match name {
+        "rustfmt" => Ok(Formatter::Rustfmt),
+        #[cfg(feature = "prettyplease")]
+        "prettyplease" => Ok(Formatter::Prettyplease),
+        #[cfg(not(feature = "prettyplease"))]
+        "prettyplease" => bail!("The `prettyplease` formatter requires the `prettyplease` feature"),
+        "none" => Ok(Formatter::None),
+        _ => bail!(
+            "Unknown formatter `{}`, expected `rustfmt`, `prettyplease` or `none`",
+            name
+        ),
+    }
+}
+
 pub fn main() -> Result<()> {
     let opts = Opts::from_args();
+    let rustfmt_args = if opts.rustfmt_args.is_empty() {
+        vec!["--edition".to_string(), "2018".to_string()]
+    } else {
+        opts.rustfmt_args.clone()
+    };
 
     // generate the Rust code
     let mut generated_file = NamedTempFile::new()?;
     Generator::builder()
         .with_root_name_str(&opts.root)
         .with_input_file(&opts.schema_path)
+        .with_rustfmt_args(&rustfmt_args)
+        .with_formatter(opts.formatter)
         .build()
         .generate_to_file(
             &generated_file
@@ -36,6 +69,18 @@ pub fn main() -> Result<()> {
                 .ok_or_else(|| anyhow!("converting output path"))?,
         )?;
 
+    if opts.formatter != Formatter::Rustfmt {
+        match &opts.output {
+            Some(path) => {
+                std::fs::copy(generated_file.path(), path)?;
+            }
+            None => {
+                copy(generated_file.as_file_mut(), &mut std::io::stdout())?;
+            }
+        }
+        return Ok(());
+    }
+
     // run it through rustfmt and write it out
     let (output_file, output_path) = NamedTempFile::new_in(
         opts.output
@@ -47,7 +92,7 @@ pub fn main() -> Result<()> {
     .context("creating temporary output file")?
     .into_parts();
     let mut formatter = Command::new("rustfmt")
-        .args(&["--edition", "2018"])
+        .args(&rustfmt_args)
         .stdin(Stdio::piped())
         .stdout(
             opts.output
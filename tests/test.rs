@@ -79,20 +79,36 @@ fn array_type() {
     let o: Option<ArrayType> = None;
     if let Some(o) = o {
         let _: Vec<i64> = o.required;
-        let _: Option<Vec<i64>> = o.optional;
+        let _: Vec<i64> = o.optional;
     }
     serde_json::from_str::<ArrayType>("{}").unwrap_err();
-    serde_json::from_str::<ArrayType>(r#"{"required": []}"#).unwrap();
+    // A missing optional array is empty
+    let o = serde_json::from_str::<ArrayType>(r#"{"required": []}"#).unwrap();
+    assert_eq!(o.optional, Vec::<i64>::new());
     assert_eq!(
         serde_json::to_string(&ArrayType {
             required: Vec::new(),
-            optional: None,
+            optional: vec![1],
         })
         .unwrap(),
-        r#"{"required":[]}"#
+        r#"{"optional":[1],"required":[]}"#
     );
 }
 
+schemafy::schemafy!(
+    root: OptionalArray
+    "tests/optional-array.json"
+);
+
+#[test]
+fn optional_array() {
+    // A struct of optional fields still derives `Default` with an array defaulted to empty
+    let o = OptionalArray::default();
+    assert_eq!(o.tags, Vec::<String>::new());
+    assert_eq!(o.name, None);
+    assert_eq!(serde_json::from_str::<OptionalArray>("{}").unwrap(), o);
+}
+
 schemafy::schemafy!(
     root: EmptyStruct
     "tests/empty-struct.json"
@@ -121,7 +137,7 @@ schemafy::schemafy!(
 #[test]
 fn root_array() {
     let a = RootArray::default();
-    let _: Option<&RootArrayItem> = a.get(0);
+    let _: Option<&RootArrayItem> = a.first();
 }
 
 schemafy::schemafy!(
@@ -146,6 +162,24 @@ fn enum_names_str() {
     assert_eq!(serde_json::to_string(&EnumNamesStr::B).unwrap(), "\"2\"");
 }
 
+schemafy::schemafy!(
+    root: EnumExtensions
+    "tests/enum-extensions.json"
+);
+
+#[test]
+fn enum_extensions() {
+    assert_eq!(
+        serde_json::to_string(&Status::Started).unwrap(),
+        "\"in_progress\""
+    );
+    assert_eq!(
+        serde_json::from_str::<Status>("\"done\"").unwrap(),
+        Status::Finished
+    );
+    assert_eq!(serde_json::to_string(&Priority::High).unwrap(), "2");
+}
+
 schemafy::schemafy!(
     root: RecursiveTypes
     "tests/recursive_types.json"
@@ -154,6 +188,37 @@ schemafy::schemafy!(
 #[allow(dead_code)]
 fn recursive_types_exist(_: RecursiveTypes) {}
 
+schemafy::schemafy!(
+    root: RecursiveRef
+    "tests/recursive-ref.json"
+);
+
+#[test]
+fn recursive_ref() {
+    let r: RecursiveRef =
+        serde_json::from_str(r#"{"parent": {"name": "a"}, "children": [{"extension": {}}]}"#)
+            .unwrap();
+    let parent: Box<RecursiveRef> = r.parent.unwrap();
+    assert_eq!(parent.name.as_deref(), Some("a"));
+    let _: Option<Box<RecursiveRef>> = r.children[0].extension.clone();
+}
+
+schemafy::schemafy!(
+    root: OptionalRecursive
+    "tests/optional-recursive.json"
+);
+
+#[test]
+fn optional_recursive() {
+    let list: OptionalRecursive =
+        serde_json::from_str(r#"{"head": {"value": 1, "next": {"value": 2, "next": null}}}"#)
+            .unwrap();
+    let head = list.head.unwrap();
+    let next: Option<Box<Node>> = head.next;
+    let _: Option<Box<Node>> = head.child;
+    assert_eq!(next.unwrap().value, 2);
+}
+
 schemafy::schemafy!(
     root: OneOfSchema
     "tests/one-of-types.json"
@@ -162,19 +227,19 @@ schemafy::schemafy!(
 #[test]
 fn one_of_parsing() {
     let t1: OneOfSchema = serde_json::from_str(r#"{"bar":2}"#).unwrap();
-    assert_eq!(t1, OneOfSchema::Variant0(OneOfSchemaVariant0 { bar: 2 }));
+    assert_eq!(t1, OneOfSchema::Variant0 { bar: 2 });
 
     let t2: OneOfSchema = serde_json::from_str(r#"{"foo":"baz"}"#).unwrap();
     assert_eq!(
         t2,
-        OneOfSchema::Variant1(OneOfSchemaVariant1 {
+        OneOfSchema::Variant1 {
             foo: "baz".to_string()
-        })
+        }
     );
 
     // This should return an error, but serde still parses it
     let t3: OneOfSchema = serde_json::from_str(r#"{"bar": 2, "foo":"baz"}"#).unwrap();
-    assert_eq!(t3, OneOfSchema::Variant0(OneOfSchemaVariant0 { bar: 2 }));
+    assert_eq!(t3, OneOfSchema::Variant0 { bar: 2 });
 
     assert!(serde_json::from_str::<OneOfSchema>(r#"{"foo":3}"#).is_err());
 }
@@ -198,3 +263,1475 @@ fn unknown_fields() {
     // non-empty struct with additionalProperties unspecified
     serde_json::from_str::<ArrayType>(r#"{"required": [], "zzz": 5}"#).unwrap();
 }
+
+schemafy::schemafy!(
+    root: OneOfStructVariants
+    "tests/one-of-struct-variants.json"
+);
+
+#[test]
+fn one_of_struct_variants() {
+    let t1: OneOfStructVariants = serde_json::from_str(r#"{"name":"a"}"#).unwrap();
+    assert_eq!(
+        t1,
+        OneOfStructVariants::Variant0(Named {
+            name: "a".to_string()
+        })
+    );
+
+    let t2: OneOfStructVariants = serde_json::from_str(r#"{"foo-bar":"baz","type":1}"#).unwrap();
+    assert_eq!(
+        t2,
+        OneOfStructVariants::Variant1 {
+            foo_bar: "baz".to_string(),
+            type_: Some(1),
+        }
+    );
+    assert_eq!(
+        serde_json::to_string(&OneOfStructVariants::Variant1 {
+            foo_bar: "baz".to_string(),
+            type_: None,
+        })
+        .unwrap(),
+        r#"{"foo-bar":"baz"}"#
+    );
+}
+
+schemafy::schemafy!(
+    root: StringLength
+    string_length_validation: true
+    "tests/string-length.json"
+);
+
+#[test]
+fn string_length() {
+    let s: StringLength = serde_json::from_str(r#"{"code": "åäö", "name": "a"}"#).unwrap();
+    assert_eq!(&*s.code, "åäö");
+    assert_eq!(s.name.as_deref().map(|name| name.as_str()), Some("a"));
+    assert_eq!(
+        serde_json::to_string(&s).unwrap(),
+        r#"{"code":"åäö","name":"a"}"#
+    );
+
+    serde_json::from_str::<StringLength>(r#"{"code": "a"}"#).unwrap_err();
+    serde_json::from_str::<StringLength>(r#"{"code": "abcd"}"#).unwrap_err();
+    serde_json::from_str::<StringLength>(r#"{"code": "ab", "name": ""}"#).unwrap_err();
+    Name::new("".into()).unwrap_err();
+    assert_eq!(
+        serde_json::to_string(&Name::new("a".into()).unwrap()).unwrap(),
+        r#""a""#
+    );
+    StringLengthCode::new("ab".into()).unwrap();
+}
+
+schemafy::schemafy!(
+    root: TypeUnion
+    untagged_type_unions: true
+    "tests/type-union.json"
+);
+
+#[test]
+fn type_union() {
+    let t: TypeUnion = serde_json::from_str(r#"{"id": "a", "value": 1}"#).unwrap();
+    assert_eq!(t.id, TypeUnionId::String("a".into()));
+    assert_eq!(t.value, Some(TypeUnionValue::Integer(1)));
+
+    let t: TypeUnion = serde_json::from_str(r#"{"id": 2, "value": 1.5}"#).unwrap();
+    assert_eq!(t.id, TypeUnionId::Integer(2));
+    assert_eq!(t.value, Some(TypeUnionValue::Number(1.5)));
+
+    let t: TypeUnion = serde_json::from_str(r#"{"id": 2, "value": {"x": 3}}"#).unwrap();
+    assert_eq!(
+        t.value,
+        Some(TypeUnionValue::Object(TypeUnionValueObject { x: Some(3) }))
+    );
+
+    let t: TypeUnion = serde_json::from_str(r#"{"id": 2, "value": null}"#).unwrap();
+    assert_eq!(t.value, None);
+
+    serde_json::from_str::<TypeUnion>(r#"{"id": true, "value": null}"#).unwrap_err();
+}
+
+mod schema_constants {
+    use serde_derive::{Deserialize, Serialize};
+    use serde_repr::{Deserialize_repr, Serialize_repr};
+
+    schemafy::schemafy!(
+        root: Root
+        schema_constants: true
+        "tests/nested.json"
+    );
+
+    schemafy::schemafy!(
+        root: EnumNamesInt
+        schema_constants: true
+        "tests/enum-names-int.json"
+    );
+
+    #[test]
+    fn schema_constants() {
+        let schema: serde_json::Value = serde_json::from_str(Defnested::SCHEMA).unwrap();
+        assert_eq!(schema["properties"]["append"]["type"], "string");
+
+        let schema: serde_json::Value = serde_json::from_str(EnumNamesInt::SCHEMA).unwrap();
+        assert_eq!(schema["enum"], serde_json::json!([1, 2]));
+    }
+}
+
+mod type_affixes {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Root
+        type_prefix: "Api"
+        type_suffix: "Dto"
+        "tests/type-affixes.json"
+    );
+
+    #[test]
+    fn type_affixes() {
+        let root: ApiRootDto = serde_json::from_str(
+            r#"{"item": {"parent": {"parent": null}}, "inline": {"name": "a"}, "choice": 1}"#,
+        )
+        .unwrap();
+        let item: ApiItemDto = root.item.unwrap();
+        let _: Option<Box<ApiItemDto>> = item.parent;
+        let _: ApiRootInlineDto = root.inline.unwrap();
+        assert_eq!(root.choice, Some(ApiRootChoiceDto::Variant1(1)));
+        assert_eq!(
+            serde_json::to_string(&ApiItemDto { parent: None }).unwrap(),
+            r#"{"parent":null}"#
+        );
+    }
+}
+
+schemafy::schemafy!(
+    root: Nullable
+    "tests/nullable.json"
+);
+
+#[test]
+fn nullable() {
+    let n: Nullable = serde_json::from_str(r#"{"required": null, "optional": 1}"#).unwrap();
+    let _: Option<String> = n.required;
+    let _: Option<i64> = n.optional;
+    let _: Option<i64> = n.multi;
+    assert_eq!(
+        serde_json::to_string(&Nullable {
+            required: None,
+            optional: None,
+            multi: None,
+        })
+        .unwrap(),
+        r#"{"required":null}"#
+    );
+}
+
+schemafy::schemafy!(
+    root: OptionalEnum
+    "tests/optional-enum.json"
+);
+
+#[test]
+fn optional_enum() {
+    // All the fields are optional or default so the struct derives `Default`
+    let o = OptionalEnum::default();
+    assert_eq!(o.optional, None);
+    assert_eq!(o.nullable, None);
+    assert_eq!(o.number, None);
+    assert_eq!(o.common, Common::default());
+
+    let o: OptionalEnum =
+        serde_json::from_str(r#"{"optional": "red", "nullable": null, "common": {}}"#).unwrap();
+    assert_eq!(o.optional, Some(Color::Red));
+}
+
+mod serde_default_container {
+    use serde_derive::{Deserialize, Serialize};
+    use serde_repr::{Deserialize_repr, Serialize_repr};
+
+    schemafy::schemafy!(
+        root: OptionalEnum
+        serde_default_container: true
+        "tests/optional-enum.json"
+    );
+
+    #[test]
+    fn serde_default_container() {
+        // `common` is required but can be filled in from its default
+        let o: OptionalEnum = serde_json::from_str("{}").unwrap();
+        assert_eq!(o, OptionalEnum::default());
+    }
+}
+
+schemafy::schemafy!(
+    root: Dependencies
+    dependency_validation: true
+    "tests/dependencies.json"
+);
+
+#[test]
+fn dependencies() {
+    let valid: Dependencies = serde_json::from_str(
+        r#"{"name": "a", "credit_card": 1, "billing-address": "b", "shipping_address": "c"}"#,
+    )
+    .unwrap();
+    assert!(valid.validate().is_ok());
+
+    let missing_billing: Dependencies =
+        serde_json::from_str(r#"{"name": "a", "credit_card": 1, "shipping_address": "c"}"#)
+            .unwrap();
+    assert_eq!(
+        missing_billing.validate().unwrap_err().to_string(),
+        "`billing-address` is required when `credit_card` is present"
+    );
+
+    let missing_shipping: Dependencies = serde_json::from_str(r#"{"name": "a"}"#).unwrap();
+    assert!(missing_shipping.validate().is_err());
+}
+
+schemafy::schemafy!(
+    root: EmptyDefinition
+    "tests/empty-definition.json"
+);
+
+#[test]
+fn empty_definition() {
+    // The empty schema accepts any value
+    let _: Anything = serde_json::Value::Null;
+    let e: EmptyDefinition = serde_json::from_str(r#"{"value": [1, "a", {}]}"#).unwrap();
+    assert_eq!(e.value, serde_json::json!([1, "a", {}]));
+}
+
+#[cfg(feature = "ordered-float")]
+mod ordered_floats {
+    use schemafy_core::OrderedFloat;
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: OrderedFloats
+        ordered_floats: true
+        "tests/ordered-floats.json"
+    );
+
+    #[test]
+    fn ordered_floats() {
+        let o: OrderedFloats =
+            serde_json::from_str(r#"{"value": 1.5, "values": [3, 1, 2]}"#).unwrap();
+        let mut values = o.values;
+        values.sort();
+        assert_eq!(
+            values,
+            [OrderedFloat(1.0), OrderedFloat(2.0), OrderedFloat(3.0)]
+        );
+
+        let keys = std::iter::once(o.value).collect::<std::collections::HashSet<_>>();
+        assert!(keys.contains(&OrderedFloat(1.5)));
+        assert_eq!(serde_json::to_string(&o.value).unwrap(), "1.5");
+    }
+}
+
+schemafy::schemafy!(
+    root: Borrow
+    borrow: true
+    "tests/borrow.json"
+);
+
+#[test]
+fn borrow() {
+    let json = String::from(
+        r#"{"name": "a", "tags": ["b"], "holder": {"person": {"name": "c"}, "next": {}}}"#,
+    );
+    let b: Borrow<'_> = serde_json::from_str(&json).unwrap();
+    let name: &str = b.name;
+    assert_eq!(name, "a");
+    assert_eq!(b.tags, vec!["b"]);
+    let holder: AHolder<'_> = b.holder.unwrap();
+    let person: BPerson<'_> = holder.person.unwrap();
+    assert_eq!(person.name, "c");
+    assert_eq!(holder.next, Some(Box::new(AHolder::default())));
+}
+
+mod validation {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Validation
+        validation: true
+        "tests/validation.json"
+    );
+
+    #[test]
+    fn validation() {
+        let valid: Validation =
+            serde_json::from_str(r#"{"name": "ab", "age": 3, "nickname": "c", "tags": ["t"]}"#)
+                .unwrap();
+        assert!(valid.validate().is_ok());
+
+        let invalid: Validation = serde_json::from_str(
+            r#"{"name": "abcde", "age": 150, "step": 0.7, "tags": [], "points": [{"x": 1}, {"x": 11}]}"#,
+        )
+        .unwrap();
+        let errors = invalid
+            .validate()
+            .unwrap_err()
+            .iter()
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "`nickname` is required when `age` is present",
+                "`/age`: 150 is greater than the maximum 150",
+                "`/name`: string of length 5 is longer than the maximum length 4",
+                "`/points/1/x`: 11 is greater than the maximum 10",
+                "`/step`: 0.7 is not a multiple of 0.5",
+                "`/tags`: array of length 0 has fewer than 1 items",
+            ]
+        );
+    }
+}
+
+mod exclusive_bounds {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: ExclusiveBounds
+        validation: true
+        "tests/exclusive-bounds.json"
+    );
+
+    #[test]
+    fn exclusive_bounds() {
+        let valid: ExclusiveBounds =
+            serde_json::from_str(r#"{"ratio": 0.5, "score": 6, "level": 3}"#).unwrap();
+        assert!(valid.validate().is_ok());
+
+        let invalid: ExclusiveBounds =
+            serde_json::from_str(r#"{"ratio": 1, "score": 5, "level": 4}"#).unwrap();
+        let errors = invalid
+            .validate()
+            .unwrap_err()
+            .iter()
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "`/level`: 4 is greater than the maximum 3",
+                "`/ratio`: 1 is greater than the maximum 1",
+                "`/score`: 5 is less than the minimum 5",
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "regex")]
+mod validation_pattern {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: ValidationPattern
+        validation: true
+        "tests/validation-pattern.json"
+    );
+
+    #[test]
+    fn validation_pattern() {
+        let valid: ValidationPattern = serde_json::from_str(r#"{"code": "ABC"}"#).unwrap();
+        assert!(valid.validate().is_ok());
+        let invalid: ValidationPattern = serde_json::from_str(r#"{"code": "ABCD"}"#).unwrap();
+        assert_eq!(invalid.validate().unwrap_err()[0].path(), "/code");
+    }
+}
+
+schemafy::schemafy!(
+    root: UniqueItems
+    unique_item_sets: true
+    "tests/unique-items.json"
+);
+
+#[test]
+fn unique_items() {
+    let u: UniqueItems = serde_json::from_str(
+        r#"{"tags": ["b", "a", "b"], "ids": [2, 1], "points": [{"x": 1.0}], "names": ["b", "b"]}"#,
+    )
+    .unwrap();
+    let tags: &std::collections::BTreeSet<String> = &u.tags;
+    assert_eq!(tags.iter().collect::<Vec<_>>(), ["a", "b"]);
+    let ids: &std::collections::BTreeSet<i64> = &u.ids;
+    assert_eq!(ids.len(), 2);
+    // Objects are not ordered so they stay in a `Vec`
+    let points: &Vec<_> = &u.points;
+    assert_eq!(points.len(), 1);
+    let names: &Vec<String> = &u.names;
+    assert_eq!(names.len(), 2);
+}
+
+mod recursion_pointer {
+    use serde_derive::{Deserialize, Serialize};
+    use std::sync::Arc;
+
+    schemafy::schemafy!(
+        root: OptionalRecursive
+        recursion_pointer: "std::sync::Arc"
+        "tests/optional-recursive.json"
+    );
+
+    #[test]
+    fn recursion_pointer() {
+        let list: OptionalRecursive =
+            serde_json::from_str(r#"{"head": {"value": 1, "next": {"value": 2, "next": null}}}"#)
+                .unwrap();
+        let head = list.head.unwrap();
+        let next: Option<Arc<Node>> = head.next.clone();
+        let _: Option<Arc<Node>> = head.child;
+        assert_eq!(next.unwrap().value, 2);
+        // Clones share the nested node
+        assert!(Arc::ptr_eq(
+            head.next.as_ref().unwrap(),
+            head.clone().next.as_ref().unwrap()
+        ));
+    }
+}
+
+schemafy::schemafy!(
+    root: Secrets
+    redact_secrets: true
+    "tests/secrets.json"
+);
+
+#[test]
+fn redact_secrets() {
+    let secrets: Secrets =
+        serde_json::from_str(r#"{"user": "a", "password": "hunter2", "token": "t"}"#).unwrap();
+    let password: &schemafy_core::Secret<String> = &secrets.password;
+    assert_eq!(password.expose(), "hunter2");
+    assert_eq!(secrets.token.as_ref().unwrap().expose(), "t");
+    assert!(!format!("{:?}", secrets).contains("hunter2"));
+    assert_eq!(
+        serde_json::to_value(&secrets).unwrap(),
+        serde_json::json!({ "user": "a" })
+    );
+}
+
+mod redact_debug {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Secrets
+        redact_debug: true
+        "tests/secrets.json"
+    );
+
+    #[test]
+    fn redact_debug() {
+        let secrets: Secrets =
+            serde_json::from_str(r#"{"user": "a", "password": "hunter2", "token": "t"}"#).unwrap();
+        assert_eq!(
+            format!("{:?}", secrets),
+            r#"Secrets { password: [redacted], token: [redacted], user: "a" }"#
+        );
+        assert_eq!(secrets.password, "hunter2");
+    }
+}
+
+schemafy::schemafy!(
+    root: HyphenatedAlias
+    "tests/hyphenated-alias.json"
+);
+
+#[test]
+fn hyphenated_alias() {
+    let h: HyphenatedAlias =
+        serde_json::from_str(r#"{"name": "a", "names": ["b"], "count": 1}"#).unwrap();
+    let name: &FooBar = &h.name;
+    let names: &Vec<FooBar> = &h.names;
+    let count: &Option<ItemCount> = &h.count;
+    assert_eq!(name, "a");
+    assert_eq!(names[0], "b");
+    assert_eq!(*count, Some(1));
+}
+
+mod bound_constants {
+    use serde_derive::{Deserialize, Serialize};
+    use serde_repr::{Deserialize_repr, Serialize_repr};
+
+    schemafy::schemafy!(
+        root: BoundConstants
+        bound_constants: true
+        string_length_validation: true
+        "tests/bound-constants.json"
+    );
+
+    #[test]
+    fn bound_constants() {
+        assert_eq!(Color::ALLOWED, ["red", "green"]);
+        assert_eq!(Level::ALLOWED, [1, 2, 3]);
+        assert_eq!(Size_::ALLOWED, ["small"]);
+        assert_eq!(Code::MIN_LENGTH, 2);
+        assert_eq!(Code::MAX_LENGTH, 4);
+    }
+}
+
+schemafy::schemafy!(
+    root: EscapedRef
+    "tests/escaped-ref.json"
+);
+
+#[test]
+fn escaped_ref() {
+    let e: EscapedRef = serde_json::from_str(r#"{"slash": "m", "space": 2}"#).unwrap();
+    let slash: &UnitName = &e.slash;
+    let space: &UnitCount = &e.space;
+    assert_eq!(slash, "m");
+    assert_eq!(*space, 2);
+}
+
+schemafy::schemafy!(
+    root: EnumCollisions
+    "tests/enum-collisions.json"
+);
+
+#[test]
+fn enum_collisions() {
+    for (json, case) in [
+        (r#"{"case": "fooBar"}"#, Case::FooBar),
+        (r#"{"case": "foo-bar"}"#, Case::FooBar2),
+        (r#"{"case": "foo_bar"}"#, Case::FooBar3),
+    ] {
+        let e: EnumCollisions = serde_json::from_str(json).unwrap();
+        assert_eq!(e.case, case);
+        assert_eq!(serde_json::to_string(&e).unwrap(), json.replace(' ', ""));
+    }
+}
+
+schemafy::schemafy!(
+    root: BigIntegers
+    "tests/big-integers.json"
+);
+
+#[test]
+fn big_integers() {
+    let b: BigIntegers = serde_json::from_str(
+        r#"{"small": 1, "balance": 18446744073709551615, "delta": -9223372036854775809, "id": 2}"#,
+    )
+    .unwrap();
+    let _: i64 = b.small;
+    let balance: u128 = b.balance;
+    let delta: i128 = b.delta;
+    let _: i128 = b.id;
+    assert_eq!(balance, u128::from(u64::MAX));
+    assert_eq!(delta, i128::from(i64::MIN) - 1);
+}
+
+schemafy::schemafy!(
+    root: AdjacentlyTagged
+    one_of_tagging: "auto"
+    "tests/adjacently-tagged.json"
+);
+
+#[test]
+fn adjacently_tagged() {
+    let json = r#"{"shape":{"type":"square-shape","data":{"side":2.0}}}"#;
+    let a: AdjacentlyTagged = serde_json::from_str(json).unwrap();
+    match &a.shape {
+        AdjacentlyTaggedShape::SquareShape(square) => assert_eq!(square.side, 2.0),
+        shape => panic!("{:?}", shape),
+    }
+    assert_eq!(serde_json::to_string(&a).unwrap(), json);
+
+    let a: AdjacentlyTagged =
+        serde_json::from_str(r#"{"shape": {"type": "circle", "data": {"radius": 1.0}}}"#).unwrap();
+    assert_eq!(
+        a.shape,
+        AdjacentlyTaggedShape::Circle(Circle { radius: 1.0 })
+    );
+    assert_eq!(a.shape.tag(), "circle");
+    let square = AdjacentlyTaggedShape::SquareShape(AdjacentlyTaggedShapeSquareShape { side: 1.0 });
+    assert_eq!(square.tag(), "square-shape");
+}
+
+schemafy::schemafy!(
+    root: ComposedItems
+    "tests/composed-items.json"
+);
+
+#[test]
+fn composed_items() {
+    let c: ComposedItems = serde_json::from_str(
+        r#"{"refs": [{"id": "a", "name": "b"}], "inline": [{"id": "c", "extra": 1}]}"#,
+    )
+    .unwrap();
+    let refs: &Vec<Labeled> = &c.refs;
+    assert_eq!(refs[0].id, "a");
+    assert_eq!(refs[0].name.as_deref(), Some("b"));
+    let inline = &c.inline[0];
+    assert_eq!(inline.id, "c");
+    assert_eq!(inline.extra, Some(1));
+}
+
+mod base_derives {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: BaseDerives
+        base_derives: "Clone, PartialEq, Eq, Hash, Debug"
+        "tests/base-derives.json"
+    );
+
+    #[test]
+    fn base_derives() {
+        let a: BaseDerives = serde_json::from_str(r#"{"name": "x", "kind": "a"}"#).unwrap();
+        let mut set = std::collections::HashSet::new();
+        set.insert(a.clone());
+        set.insert(a);
+        assert_eq!(set.len(), 1);
+    }
+}
+
+schemafy::schemafy!(
+    root: AllOfRequired
+    "tests/all-of-required.json"
+);
+
+#[test]
+fn all_of_required() {
+    let a: AllOfRequired = serde_json::from_str(r#"{"strict": {"x": 1}}"#).unwrap();
+    let strict: StrictAccount = a.strict;
+    let x: i64 = strict.x;
+    let y: Option<String> = strict.y;
+    assert_eq!((x, y), (1, None));
+    assert!(a.inline.is_none());
+
+    let a: AllOfRequired =
+        serde_json::from_str(r#"{"strict": {"x": 1}, "inline": {"y": "a"}}"#).unwrap();
+    let inline: AllOfRequiredInline = a.inline.unwrap();
+    let y: String = inline.y;
+    let x: Option<i64> = inline.x;
+    assert_eq!((x, y.as_str()), (None, "a"));
+    assert!(serde_json::from_str::<AllOfRequired>(r#"{"strict": {"y": "a"}}"#).is_err());
+}
+
+schemafy::schemafy!(
+    root: ConstEnum
+    "tests/const-enum.json"
+);
+
+#[test]
+fn const_enum() {
+    let c: ConstEnum = serde_json::from_str(
+        r#"{"listed": "dark-ish", "any_of": "dark-ish", "one_of": "light", "inline": "b"}"#,
+    )
+    .unwrap();
+    assert_eq!(c.listed, Some(ListedShade::DarkIsh));
+    assert_eq!(c.any_of, Some(AnyOfShade::DarkIsh));
+    assert_eq!(c.one_of, Some(OneOfShade::Light));
+    let inline: Option<String> = c.inline;
+    assert_eq!(inline.as_deref(), Some("b"));
+    assert_eq!(
+        serde_json::to_string(&AnyOfShade::DarkIsh).unwrap(),
+        r#""dark-ish""#
+    );
+    assert!(serde_json::from_str::<OneOfShade>(r#""dim""#).is_err());
+    // The variants are named after the titles of the branches
+    let titled: Vec<TitledShade> = serde_json::from_str(r#"["l", "d", "dark-ish"]"#).unwrap();
+    assert_eq!(
+        titled,
+        vec![
+            TitledShade::Light,
+            TitledShade::VeryDark,
+            TitledShade::DarkIsh
+        ]
+    );
+}
+
+schemafy::schemafy!(
+    root: MapSize
+    map_size_validation: true
+    "tests/map-size.json"
+);
+
+#[test]
+fn map_size() {
+    let m: MapSize = serde_json::from_str(r#"{"labels": {"a": "b"}}"#).unwrap();
+    let labels: &MapSizeLabels = &m.labels;
+    assert_eq!(labels.get("a").map(String::as_str), Some("b"));
+    let extra: Option<::std::collections::BTreeMap<String, i64>> = m.extra;
+    assert_eq!(extra, None);
+    assert_eq!(serde_json::to_string(labels).unwrap(), r#"{"a":"b"}"#);
+
+    serde_json::from_str::<MapSize>(r#"{"labels": {}}"#).unwrap_err();
+    serde_json::from_str::<MapSize>(r#"{"labels": {"a": "", "b": "", "c": ""}}"#).unwrap_err();
+    MapSizeLabels::new(Default::default()).unwrap_err();
+
+    let entries: Vec<(&String, &String)> = labels.into_iter().collect();
+    assert_eq!(entries, [(&"a".to_string(), &"b".to_string())]);
+    let labels = MapSizeLabels::new(
+        m.labels
+            .into_iter()
+            .chain(vec![("c".into(), "d".into())])
+            .collect(),
+    )
+    .unwrap();
+    let keys: Vec<String> = labels.into_iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["a", "c"]);
+}
+
+schemafy::schemafy!(
+    root: JsonContent
+    "tests/json-content.json"
+);
+
+#[test]
+fn json_content() {
+    let json = r#"{"payload":"{\"id\":1}","raw":"{}"}"#;
+    let content: JsonContent = serde_json::from_str(json).unwrap();
+    let payload: &JsonContentPayload = &content.payload;
+    let tags: &Option<Vec<String>> = &content.tags;
+    let raw: &Option<String> = &content.raw;
+    assert_eq!(payload.id, 1);
+    assert_eq!(*tags, None);
+    assert_eq!(raw.as_deref(), Some("{}"));
+    assert_eq!(serde_json::to_string(&content).unwrap(), json);
+
+    let content: JsonContent =
+        serde_json::from_str(r#"{"payload":"{\"id\":1}","tags":"[\"a\"]"}"#).unwrap();
+    assert_eq!(content.tags, Some(vec!["a".to_string()]));
+    assert!(serde_json::from_str::<JsonContent>(r#"{"payload":"{}"}"#).is_err());
+}
+
+schemafy::schemafy!(
+    root: Extends
+    "tests/extends.json"
+);
+
+#[test]
+fn extends() {
+    let json = r#"{"name":"cat","legs":4}"#;
+    let e: Extends = serde_json::from_str(json).unwrap();
+    let base: &AnimalBase = &e.base;
+    assert_eq!(base.name, "cat");
+    assert_eq!(base.sound, None);
+    assert_eq!(e.legs, 4);
+    assert_eq!(serde_json::to_string(&e).unwrap(), json);
+    assert!(serde_json::from_str::<Extends>(r#"{"legs":4}"#).is_err());
+}
+
+schemafy::schemafy!(
+    root: ValidationNot
+    validation: true
+    "tests/validation-not.json"
+);
+
+#[test]
+fn validation_not() {
+    let valid: ValidationNot =
+        serde_json::from_str(r#"{"username": "alice", "port": 80}"#).unwrap();
+    assert!(valid.validate().is_ok());
+
+    let invalid: ValidationNot =
+        serde_json::from_str(r#"{"username": "root", "port": 22}"#).unwrap();
+    let errors = invalid.validate().unwrap_err();
+    let paths = errors.iter().map(|err| err.path()).collect::<Vec<_>>();
+    assert_eq!(paths, ["/port", "/username"]);
+    assert_eq!(
+        errors[1].to_string(),
+        r#"`/username`: `"root"` matches the schema `{"enum":["root","admin"]}` which it must not"#
+    );
+}
+
+mod ref_modules_common {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!("tests/ref-modules-common.json");
+}
+
+mod ref_modules {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Resident
+        ref_modules: "ref-modules-common.json=super::ref_modules_common"
+        "tests/ref-modules.json"
+    );
+}
+
+#[test]
+fn ref_modules() {
+    let json = r#"{"home":{"city":"Oslo","street":"Storgata 1"},"name":"Kari","previous":[]}"#;
+    let resident: ref_modules::Resident = serde_json::from_str(json).unwrap();
+    let home: &ref_modules_common::PostalAddress = &resident.home;
+    assert_eq!(home.city, "Oslo");
+    assert_eq!(resident.previous, vec![]);
+    assert_eq!(serde_json::to_string(&resident).unwrap(), json);
+}
+
+schemafy::schemafy!(
+    root: RustOptional
+    "tests/rust-optional.json"
+);
+
+#[test]
+fn rust_optional() {
+    let r: RustOptional = serde_json::from_str(r#"{"label": "a", "note": "b"}"#).unwrap();
+    let id: Option<i64> = r.id;
+    let label: String = r.label;
+    let note: String = r.note;
+    assert_eq!((id, label.as_str(), note.as_str()), (None, "a", "b"));
+    assert!(serde_json::from_str::<RustOptional>(r#"{"id": 1, "note": "b"}"#).is_err());
+}
+
+schemafy::schemafy!(
+    root: JsonFromStr
+    json_from_str: true
+    "tests/json-from-str.json"
+);
+
+#[test]
+fn json_from_str() {
+    let book: JsonFromStr = r#"{"title": "Dune", "pages": 412}"#.parse().unwrap();
+    assert_eq!(book.title, "Dune");
+    assert_eq!(book.pages, Some(412));
+    assert!("{}".parse::<JsonFromStr>().is_err());
+}
+
+schemafy::schemafy!(
+    root: AdditionalPropertiesTrue
+    "tests/additional-properties-true.json"
+);
+
+#[test]
+fn additional_properties_true() {
+    let a: AdditionalPropertiesTrue =
+        serde_json::from_str(r#"{"name": "a", "extra": {"b": [1]}, "unknown": 2}"#).unwrap();
+    let extra: ::std::collections::BTreeMap<String, serde_json::Value> = a.extra.unwrap();
+    assert_eq!(extra["b"], serde_json::json!([1]));
+}
+
+schemafy::schemafy!(
+    root: ScreamingPriority
+    "tests/screaming-enum.json"
+);
+
+#[test]
+fn screaming_snake_enum() {
+    let p: ScreamingPriority = serde_json::from_str(r#""VERY_HIGH""#).unwrap();
+    assert_eq!(p, ScreamingPriority::VeryHigh);
+    assert_eq!(
+        serde_json::to_string(&ScreamingPriority::P1Urgent).unwrap(),
+        r#""P1_URGENT""#
+    );
+}
+
+mod openapi_components {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!("tests/openapi-components.json");
+}
+
+#[test]
+fn openapi_components() {
+    use openapi_components::{Owner, Pet, PetKind};
+
+    let pet: Pet =
+        serde_json::from_str(r#"{"name": "Rex", "kind": "dog", "owner": {"email": "a@b.c"}}"#)
+            .unwrap();
+    assert_eq!(pet.kind, PetKind::Dog);
+    let owner: Owner = pet.owner.unwrap();
+    assert_eq!(owner.email.as_deref(), Some("a@b.c"));
+}
+
+mod camel_case {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        identifier_case: "camel"
+        "tests/identifier-case.json"
+    );
+}
+
+mod preserve_case {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        identifier_case: "preserve"
+        "tests/identifier-case.json"
+    );
+}
+
+#[test]
+fn identifier_case() {
+    let json = r#"{"main_owner":{"first_name":"Ada","lastName":"L","type":"cat"}}"#;
+
+    let camel: camel_case::Household = serde_json::from_str(json).unwrap();
+    let owner: camel_case::PetOwner = camel.mainOwner.unwrap();
+    assert_eq!(owner.firstName.as_deref(), Some("Ada"));
+    assert_eq!(owner.lastName.as_deref(), Some("L"));
+    assert_eq!(owner.type_.as_deref(), Some("cat"));
+
+    let preserved: preserve_case::household = serde_json::from_str(json).unwrap();
+    assert_eq!(serde_json::to_string(&preserved).unwrap(), json);
+    let owner: preserve_case::pet_owner = preserved.main_owner.unwrap();
+    assert_eq!(owner.first_name.as_deref(), Some("Ada"));
+    assert_eq!(owner.lastName.as_deref(), Some("L"));
+    assert_eq!(owner.type_.as_deref(), Some("cat"));
+}
+
+schemafy::schemafy!(
+    root: RefChain
+    "tests/ref-chain.json"
+);
+
+#[test]
+fn ref_chain() {
+    let chain: RefChain = serde_json::from_str(r#"{"owner": {"name": "Ada"}}"#).unwrap();
+    let person: ChainPerson = chain.owner;
+    assert_eq!(person.name, "Ada");
+    let _: Owner = person.clone();
+    let _: Member = person;
+}
+
+schemafy::schemafy!(
+    root: ReadOnlyTask
+    eq_ignore_read_only: true
+    "tests/read-only-eq.json"
+);
+
+#[test]
+fn eq_ignore_read_only() {
+    let sent: ReadOnlyTask = serde_json::from_str(r#"{"title": "a"}"#).unwrap();
+    let returned: ReadOnlyTask =
+        serde_json::from_str(r#"{"id": 1, "created": "2024-01-01", "title": "a"}"#).unwrap();
+    assert_eq!(sent, returned);
+    let changed: ReadOnlyTask = serde_json::from_str(r#"{"title": "a", "done": true}"#).unwrap();
+    assert_ne!(sent, changed);
+}
+
+schemafy::schemafy!(
+    root: EnumDefault
+    "tests/enum-default.json"
+);
+
+#[test]
+fn enum_default() {
+    let order: EnumDefault = serde_json::from_str(r#"{"extra": "oat"}"#).unwrap();
+    let size: &CupSize = &order.size;
+    assert_eq!(*size, CupSize::Tall);
+    assert_eq!(order.milk, Milk::Whole);
+    assert_eq!(order.extra, Milk::Oat);
+    assert_eq!(
+        serde_json::to_string(&order).unwrap(),
+        r#"{"extra":"oat","milk":"whole","size":"TALL"}"#
+    );
+}
+
+schemafy::schemafy!(
+    root: Library
+    output_module: "library"
+    module_doc: true
+    "tests/output-module.json"
+);
+
+#[test]
+fn output_module() {
+    let library: library::Library =
+        serde_json::from_str(r#"{"books": [{"isbn": "0-19-852663-6"}]}"#).unwrap();
+    let books: Vec<library::Book> = library.books;
+    assert_eq!(books[0].isbn, "0-19-852663-6");
+}
+
+schemafy::schemafy!(
+    root: StringFormats
+    string_formats: true
+    "tests/string-formats.json"
+);
+
+#[test]
+fn string_formats() {
+    let json = r#"{"address":"127.0.0.1","born":"2000-01-01","contact":"a@example.com","homepage":"https://example.com/","host":"example.com"}"#;
+    let formats: StringFormats = serde_json::from_str(json).unwrap();
+    let contact: &schemafy_core::Email = &formats.contact;
+    assert_eq!(contact.as_str(), "a@example.com");
+    assert_eq!(&*formats.host, "example.com");
+    assert!(formats.homepage.starts_with("https:"));
+    assert_eq!(formats.address, std::net::Ipv4Addr::LOCALHOST);
+    let born: Option<String> = formats.born.clone();
+    assert_eq!(born.as_deref(), Some("2000-01-01"));
+    assert_eq!(serde_json::to_string(&formats).unwrap(), json);
+
+    let invalid = json.replace("a@example.com", "example.com");
+    assert!(serde_json::from_str::<StringFormats>(&invalid).is_err());
+}
+
+schemafy::schemafy!(
+    root: BlankKeys
+    "tests/blank-keys.json"
+);
+
+#[test]
+fn blank_keys() {
+    let json = r#"{"":"empty"," ":1,"  ":true}"#;
+    let keys: BlankKeys = serde_json::from_str(json).unwrap();
+    assert_eq!(keys.field_, "empty");
+    assert_eq!(keys.field_1, Some(1));
+    assert_eq!(keys.field_2, Some(true));
+    assert_eq!(serde_json::to_string(&keys).unwrap(), json);
+}
+
+mod reexports {
+    pub use serde;
+    pub use serde_json;
+}
+
+mod custom_serde_path {
+    schemafy::schemafy!(
+        root: CustomSerdePath
+        serde_path: "crate::reexports::serde"
+        serde_json_path: "crate::reexports::serde_json"
+        json_from_str: true
+        "tests/custom-serde-path.json"
+    );
+}
+
+#[test]
+fn custom_serde_path() {
+    use custom_serde_path::{CustomSerdePath, Status};
+
+    let json = r#"{"owner":{"name":"a"},"payload":[1],"status":"open"}"#;
+    let value: CustomSerdePath = json.parse().unwrap();
+    assert_eq!(value.payload, serde_json::json!([1]));
+    assert_eq!(value.status, Some(Status::Open));
+    assert_eq!(serde_json::to_string(&value).unwrap(), json);
+}
+
+schemafy::schemafy!(
+    root: SkipEmptyCollections
+    skip_empty_collections: true
+    "tests/skip-empty-collections.json"
+);
+
+#[test]
+fn skip_empty_collections() {
+    let value: SkipEmptyCollections = serde_json::from_str(r#"{"name": "a"}"#).unwrap();
+    let tags: &Vec<String> = &value.tags;
+    assert!(tags.is_empty());
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#"{"name":"a"}"#);
+
+    let value: SkipEmptyCollections =
+        serde_json::from_str(r#"{"name": "a", "tags": "b"}"#).unwrap();
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"{"name":"a","tags":"b"}"#
+    );
+}
+
+mod skip_definition {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: SkipDefinition
+        "tests/skip-definition.json"
+    );
+
+    // These would conflict with generated types of the same names
+    pub struct Scaffolding;
+    pub struct Helper;
+}
+
+#[test]
+fn skip_definition() {
+    use skip_definition::{Helper, Kept, Scaffolding, SkipDefinition};
+
+    let value: SkipDefinition = serde_json::from_str(r#"{"kept": {"id": 1}}"#).unwrap();
+    assert_eq!(value.kept, Some(Kept { id: Some(1) }));
+    let (Scaffolding, Helper) = (Scaffolding, Helper);
+}
+
+mod preserved_self_ref {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        identifier_case: "preserve"
+        "tests/preserved-self-ref.json"
+    );
+}
+
+#[test]
+fn preserved_self_ref() {
+    use preserved_self_ref::{list_node, tree_node};
+
+    let node: tree_node =
+        serde_json::from_str(r#"{"label": "a", "parent": {"label": "b"}}"#).unwrap();
+    let parent: Box<tree_node> = node.parent.unwrap();
+    assert_eq!(parent.label, "b");
+    let _: fn(list_node) -> Box<list_node> = |node| node.next;
+}
+
+#[test]
+fn unit_enums_are_hashable() {
+    let mut counts = std::collections::HashMap::new();
+    for milk in [Milk::Oat, Milk::Whole, Milk::Oat] {
+        *counts.entry(milk).or_insert(0) += 1;
+    }
+    assert_eq!(counts[&Milk::Oat], 2);
+    assert_eq!(counts[&Milk::Whole], 1);
+
+    let types: std::collections::HashSet<SimpleTypes> =
+        vec![SimpleTypes::String, SimpleTypes::String]
+            .into_iter()
+            .collect();
+    assert_eq!(types.len(), 1);
+}
+
+schemafy::schemafy!(
+    root: UntaggedFallbacks
+    untagged_fallbacks: true
+    "tests/untagged-fallbacks.json"
+);
+
+#[test]
+fn untagged_fallbacks() {
+    use UntaggedFallbacksLocation as Location;
+
+    let parse = |json: &str| serde_json::from_str::<UntaggedFallbacks>(json).unwrap();
+    assert_eq!(
+        parse(r#"{"location": {"lat": 1.0, "lon": 2.0}}"#).location,
+        Location::Coordinates(Coordinates { lat: 1.0, lon: 2.0 })
+    );
+    assert_eq!(
+        parse(r#"{"location": "home"}"#).location,
+        Location::String("home".into())
+    );
+    assert_eq!(parse(r#"{"location": 3}"#).location, Location::Integer(3));
+    let other = parse(r#"{"location": {"hint": "north"}, "id": 4}"#);
+    assert_eq!(
+        other.location,
+        Location::Other(serde_json::json!({"hint": "north"}))
+    );
+    assert_eq!(other.id, Some(UntaggedFallbacksId::Integer(4)));
+    assert_eq!(
+        serde_json::to_string(&other).unwrap(),
+        r#"{"id":4,"location":{"hint":"north"}}"#
+    );
+}
+
+mod nested_refs {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: NestedRefs
+        "tests/nested-refs.json"
+    );
+
+    #[test]
+    fn nested_refs() {
+        let refs = NestedRefs {
+            street: Some("Main Street".into()),
+            location: AddressGeo { lat: 1.0, lon: 2.0 },
+            latitude: Some(1.0),
+            tag: Some(AddressItemTags { name: None }),
+            count: Some(3),
+            items: Some(Items::from("box")),
+        };
+        let address = Address {
+            street: refs.street.clone(),
+            geo: Some(refs.location.clone()),
+            tags: refs.tag.clone().into_iter().collect(),
+            extra: Some(
+                refs.count
+                    .map(|count| ("count".to_string(), count))
+                    .into_iter()
+                    .collect(),
+            ),
+        };
+        assert_eq!(address.geo.as_ref().map(|geo| geo.lat), refs.latitude);
+    }
+}
+
+schemafy::schemafy!(
+    root: MergePatch
+    merge_patch: true
+    "tests/merge-patch.json"
+);
+
+#[test]
+fn merge_patch() {
+    let mut value = MergePatch {
+        name: "Ada".into(),
+        nickname: Some("Countess".into()),
+        address: Some(MergePatchAddress {
+            city: "London".into(),
+            zip: Some("W1".into()),
+        }),
+    };
+    value
+        .apply_patch(&serde_json::json!({
+            "nickname": null,
+            "address": { "zip": null, "city": "Paris" }
+        }))
+        .unwrap();
+    assert_eq!(
+        value,
+        MergePatch {
+            name: "Ada".into(),
+            nickname: None,
+            address: Some(MergePatchAddress {
+                city: "Paris".into(),
+                zip: None,
+            }),
+        }
+    );
+
+    // A required field can not be removed
+    assert!(value
+        .apply_patch(&serde_json::json!({ "name": null }))
+        .is_err());
+    assert_eq!(value.name, "Ada");
+}
+
+schemafy::schemafy!(
+    root: NumericKeys
+    "tests/numeric-keys.json"
+);
+
+#[test]
+fn numeric_keys() {
+    let json = r#"{"2x":2,"3d_model":"cube","42":true}"#;
+    let keys: NumericKeys = serde_json::from_str(json).unwrap();
+    assert_eq!(keys._3d_model, "cube");
+    assert_eq!(keys._2x, Some(2));
+    assert_eq!(keys._42, Some(true));
+    assert_eq!(serde_json::to_string(&keys).unwrap(), json);
+}
+
+schemafy::schemafy!(
+    root: LiteralDefault
+    "tests/literal-default.json"
+);
+
+#[test]
+fn literal_default() {
+    let config: LiteralDefault = serde_json::from_str(r#"{"port": 80}"#).unwrap();
+    let host: &String = &config.host;
+    assert_eq!(host, "localhost");
+    assert_eq!(config.port, 80);
+    assert_eq!(config.ratio, 0.5);
+    assert!(config.verbose);
+    assert_eq!(config.mode, Mode::Fast);
+    // A nullable field stays an `Option`
+    assert_eq!(config.user, None);
+}
+
+mod root_ref {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Animal
+        "tests/root-ref.json"
+    );
+
+    #[test]
+    fn root_ref() {
+        // The root is an alias of the type it refers to instead of a copy of it
+        let animal: Animal = Pet {
+            name: "Rex".to_string(),
+        };
+        assert_eq!(
+            serde_json::from_str::<Animal>(r#"{"name":"Rex"}"#).unwrap(),
+            animal
+        );
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod unix_timestamps {
+    use schemafy_core::chrono::{TimeZone, Utc};
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Record
+        unix_timestamps: true
+        "tests/unix-timestamps.json"
+    );
+
+    #[test]
+    fn unix_timestamps() {
+        let json = r#"{"created":1700000000,"deleted":null,"updated":1700000060,"version":3}"#;
+        let record: Record = serde_json::from_str(json).unwrap();
+        assert_eq!(record.created, Utc.timestamp_opt(1700000000, 0).unwrap());
+        assert_eq!(record.updated, Utc.timestamp_opt(1700000060, 0).single());
+        assert_eq!(record.deleted, None);
+        let version: Option<i64> = record.version;
+        assert_eq!(version, Some(3));
+        assert_eq!(serde_json::to_string(&record).unwrap(), json);
+
+        let record: Record = serde_json::from_str(r#"{"created":0,"deleted":60}"#).unwrap();
+        assert_eq!(record.updated, None);
+        assert_eq!(record.deleted, Utc.timestamp_opt(60, 0).single());
+    }
+}
+
+schemafy::schemafy!(
+    root: StringIntegers
+    string_integers: true
+    "tests/string-integers.json"
+);
+
+#[test]
+fn string_integers() {
+    let json = r#"{"balance":"-42","id":"12345678901234567","name":"a","previous":null}"#;
+    let account: StringIntegers = serde_json::from_str(json).unwrap();
+    let id: u64 = account.id;
+    assert_eq!(id, 12345678901234567);
+    assert_eq!(account.balance, Some(-42));
+    assert_eq!(account.previous, None);
+    assert_eq!(account.name.as_deref(), Some("a"));
+    assert_eq!(serde_json::to_string(&account).unwrap(), json);
+
+    let account: StringIntegers = serde_json::from_str(r#"{"id":"1","previous":"7"}"#).unwrap();
+    assert_eq!(account.balance, None);
+    assert_eq!(account.previous, Some(7));
+    assert!(serde_json::from_str::<StringIntegers>(r#"{"id":1,"previous":null}"#).is_err());
+}
+
+mod typed_map_keys {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Shop
+        typed_map_keys: true
+        "tests/typed-map-keys.json"
+    );
+
+    #[test]
+    fn typed_map_keys() {
+        let json = r#"{"labels":{"a":"b"},"prices":{"small":1.0,"large":2.5},"stock":{"green":3}}"#;
+        let shop: Shop = serde_json::from_str(json).unwrap();
+        assert_eq!(shop.prices.as_ref().unwrap()[&ShopPricesKey::Small], 1.0);
+        assert_eq!(shop.stock.as_ref().unwrap()[&Color::Green], 3);
+        // Without an enum the keys are strings
+        assert_eq!(shop.labels.as_ref().unwrap()["a"], "b");
+        assert_eq!(serde_json::to_string(&shop).unwrap(), json);
+        assert!(serde_json::from_str::<Shop>(r#"{"prices":{"medium":1.0}}"#).is_err());
+    }
+}
+
+mod box_variants {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        box_variants: true
+        "tests/box-variants.json"
+    );
+
+    #[test]
+    fn box_variants() {
+        let square: Shape = serde_json::from_str(r#"{"points":[0.0,1.0]}"#).unwrap();
+        assert_eq!(
+            square,
+            Shape::Variant0(Box::new(Square {
+                points: vec![0.0, 1.0]
+            }))
+        );
+        let circle: Shape = serde_json::from_str(r#"{"radius":2.0}"#).unwrap();
+        assert_eq!(
+            circle,
+            Shape::Variant1(Box::new(ShapeVariant1 { radius: 2.0 }))
+        );
+        // Only structs are boxed
+        let name: Shape = serde_json::from_str(r#""circle""#).unwrap();
+        assert_eq!(name, Shape::Variant2("circle".to_string()));
+        assert_eq!(serde_json::to_string(&circle).unwrap(), r#"{"radius":2.0}"#);
+    }
+}
+
+mod example_fixtures {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Developer
+        example_fixtures: true
+        "tests/example-fixtures.json"
+    );
+
+    #[test]
+    fn example_fixtures() {
+        assert_eq!(
+            Developer::example_0(),
+            Developer {
+                name: "ferris".to_string(),
+                level: Some(Level::Expert),
+            }
+        );
+        assert_eq!(Developer::example_1().level, None);
+        assert_eq!(Level::example_0(), Level::Beginner);
+    }
+}
+
+schemafy::schemafy!(
+    root: Tuple
+    "tests/tuple.json"
+);
+
+#[test]
+fn tuple() {
+    let json = r#"{"entry":["a",1],"names":["b","c"],"point":[1.0,2.0]}"#;
+    let tuple: Tuple = serde_json::from_str(json).unwrap();
+    let point: (f64, f64) = tuple.point;
+    assert_eq!(point, (1.0, 2.0));
+    assert_eq!(tuple.entry, Some(("a".to_string(), 1)));
+    // Without `"items": false` the other items are of the type of `items`
+    assert_eq!(tuple.names, vec!["b", "c"]);
+    assert_eq!(serde_json::to_string(&tuple).unwrap(), json);
+    // `"items": false` allows no other items
+    assert!(serde_json::from_str::<Tuple>(r#"{"point":[1.0,2.0,3.0]}"#).is_err());
+}
+
+mod str_eq {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        str_eq: true
+        "tests/str-eq.json"
+    );
+
+    #[test]
+    fn str_eq() {
+        assert!(Status::Active == "active");
+        assert!(Status::OnHold == "on-hold");
+        assert!(Status::OnHold != "OnHold");
+        assert!(Status::Active == *"active".to_string());
+        assert!(Risk::HighRisk == "HIGH_RISK");
+        assert!(Color_::Red == "red");
+    }
+}
+
+mod null_branch {
+    use serde_derive::{Deserialize, Serialize};
+
+    schemafy::schemafy!(
+        root: Issue
+        "tests/null-branch.json"
+    );
+
+    #[test]
+    fn null_branch() {
+        let json = r#"{"contact":null,"owner":{"name":"a"},"reviewer":null}"#;
+        let issue: Issue = serde_json::from_str(json).unwrap();
+        let owner: Option<Person> = issue.owner;
+        assert_eq!(owner.unwrap().name, "a");
+        assert_eq!(issue.reviewer, None);
+        assert_eq!(issue.contact, None);
+
+        let issue: Issue =
+            serde_json::from_str(r#"{"contact":3,"reviewer":{"name":"b"}}"#).unwrap();
+        assert_eq!(issue.contact, Some(IssueContact::Variant1(3)));
+        assert_eq!(
+            issue.reviewer.map(|reviewer| reviewer.name).as_deref(),
+            Some("b")
+        );
+        let maybe_person: MaybePerson = None;
+        assert_eq!(maybe_person, None);
+    }
+}